@@ -0,0 +1,50 @@
+// Compares hashing a 10k-key trie from scratch, which is what every call to
+// Trie::hash cost before the nodes cached their encoding and hash, with hashing
+// it again after a single put, which only encodes the nodes on the path of the
+// key.
+//
+// cargo bench --bench hash
+
+use std::time::{Duration, Instant};
+
+use trie::Trie;
+
+const KEYS: u32 = 10_000;
+const UPDATES: u32 = 1_000;
+
+fn build() -> Trie {
+    let mut tr = Trie::new();
+    for i in 0..KEYS {
+        tr.put(&rlp::encode(&i), &[i as u8; 40]);
+    }
+    tr
+}
+
+fn main() {
+    // a freshly built trie has no cached hashes
+    let rounds = 10;
+    let mut full = Duration::ZERO;
+    for _ in 0..rounds {
+        let tr = build();
+        let start = Instant::now();
+        tr.hash();
+        full += start.elapsed();
+    }
+    let full = full / rounds;
+
+    let mut tr = build();
+    tr.hash();
+
+    let mut incremental = Duration::ZERO;
+    for i in 0..UPDATES {
+        tr.put(&rlp::encode(&(i * 7 % KEYS)), &[i as u8; 41]);
+        let start = Instant::now();
+        tr.hash();
+        incremental += start.elapsed();
+    }
+    let incremental = incremental / UPDATES;
+
+    println!("hash of a {}-key trie from scratch: {:?}", KEYS, full);
+    println!("hash after one put:               {:?}", incremental);
+    println!("speedup:                           {:.0}x", full.as_secs_f64() / incremental.as_secs_f64());
+}
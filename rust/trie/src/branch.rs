@@ -0,0 +1,181 @@
+use rlp::RlpStream;
+
+use crate::nibbles::Nibble;
+use crate::nodes::{Node, NodeCache};
+
+// type BranchNode struct {
+//   Branches [16]Node
+//   Value    []byte
+// }
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct BranchNode {
+    pub(crate) branches: [Node; 16],
+    pub(crate) value: Option<Vec<u8>>,
+    pub(crate) cache: NodeCache,
+}
+
+impl BranchNode {
+    // func NewBranchNode() *BranchNode {
+    //   return &BranchNode{
+    //     Branches: [16]Node{},
+    //   }
+    // }
+    pub fn new() -> BranchNode {
+        BranchNode::default()
+    }
+
+    // the fields are read-only outside of the crate, see NodeCache
+    pub fn branch(&self, nibble: Nibble) -> &Node {
+        &self.branches[nibble.to_usize()]
+    }
+
+    pub fn value(&self) -> Option<&[u8]> {
+        self.value.as_deref()
+    }
+
+    // func (b BranchNode) Hash() []byte {
+    //   return crypto.Keccak256(b.Serialize())
+    // }
+    pub fn hash(&self) -> Vec<u8> {
+        self.cache.hash(|| self.encode()).to_vec()
+    }
+
+    // func (b *BranchNode) SetBranch(nibble Nibble, node Node) {
+    //   b.Branches[int(nibble)] = node
+    // }
+    pub fn set_branch(&mut self, nibble: Nibble, node: Node) {
+        self.branches[nibble.to_usize()] = node;
+        self.cache.clear();
+    }
+
+    // func (b *BranchNode) RemoveBranch(nibble Nibble) {
+    //   b.Branches[int(nibble)] = nil
+    // }
+    pub fn remove_branch(&mut self, nibble: Nibble) {
+        self.branches[nibble.to_usize()] = Node::Empty;
+        self.cache.clear();
+    }
+
+    // func (b *BranchNode) SetValue(value []byte) {
+    //   b.Value = value
+    // }
+    pub fn set_value(&mut self, value: Vec<u8>) {
+        self.value = Some(value);
+        self.cache.clear();
+    }
+
+    // func (b *BranchNode) RemoveValue() {
+    //   b.Value = nil
+    // }
+    pub fn remove_value(&mut self) {
+        self.value = None;
+        self.cache.clear();
+    }
+
+    // func (b BranchNode) Raw() []interface{} {
+    //   hashes := make([]interface{}, 17)
+    //   for i := 0; i < 16; i++ {
+    //     if b.Branches[i] == nil {
+    //       hashes[i] = EmptyNodeRaw
+    //     } else {
+    //       node := b.Branches[i]
+    //       if len(Serialize(node)) >= 32 {
+    //         hashes[i] = node.Hash()
+    //       } else {
+    //         // if node can be serialized to less than 32 bits, then
+    //         // use Serialized directly.
+    //         // it has to be ">=", rather than ">",
+    //         // so that when deserialized, the content can be distinguished
+    //         // by length
+    //         hashes[i] = node.Raw()
+    //       }
+    //     }
+    //   }
+    //
+    //   hashes[16] = b.Value
+    //   return hashes
+    // }
+    pub fn raw(&self, s: &mut RlpStream) {
+        s.begin_list(17);
+        for node in self.branches.iter() {
+            if node.is_empty() {
+                s.append_empty_data();
+            } else {
+                node.child_ref().append_to(s);
+            }
+        }
+
+        match &self.value {
+            Some(value) => s.append(value),
+            None => s.append_empty_data(),
+        };
+    }
+
+    // func (b BranchNode) Serialize() []byte {
+    //   return Serialize(b)
+    // }
+    pub fn serialize(&self) -> Vec<u8> {
+        self.encoded().to_vec()
+    }
+
+    // encoded is Serialize without copying the cached encoding.
+    pub(crate) fn encoded(&self) -> &[u8] {
+        self.cache.encoded(|| self.encode())
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut s = RlpStream::new();
+        self.raw(&mut s);
+        s.out().to_vec()
+    }
+
+    // func (b BranchNode) HasValue() bool {
+    //   return b.Value != nil
+    // }
+    pub fn has_value(&self) -> bool {
+        self.value.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::leaf::LeafNode;
+
+    // func TestBranch(t *testing.T) {
+    //   nibbles, value := []byte{5, 0, 6}, []byte("coin")
+    //   leaf, err := NewLeafNodeFromNibbleBytes(nibbles, value)
+    //   require.NoError(t, err)
+    //
+    //   b := NewBranchNode()
+    //   b.SetBranch(0, leaf)
+    //   b.SetValue([]byte("verb")) // set the value for verb
+    //
+    //   require.Equal(t, "ddc882350684636f696e8080808080808080808080808080808476657262",
+    //     fmt.Sprintf("%x", b.Serialize()))
+    //   require.Equal(t, "d757709f08f7a81da64a969200e59ff7e6cd6b06674c3f668ce151e84298aa79",
+    //     fmt.Sprintf("%x", b.Hash()))
+    //
+    // }
+    #[test]
+    fn test_branch() {
+        let nibbles = [5, 0, 6];
+        let value = b"coin";
+        let leaf = LeafNode::new_from_nibble_bytes(&nibbles, value).unwrap();
+
+        let mut b = BranchNode::new();
+        b.set_branch(Nibble(0), Node::Leaf(leaf));
+        b.set_value(b"verb".to_vec()); // set the value for verb
+        assert_eq!(Some(&b"verb"[..]), b.value());
+        assert!(matches!(b.branch(Nibble(0)), Node::Leaf(leaf) if leaf.value() == b"coin"));
+
+        assert_eq!(
+            "ddc882350684636f696e8080808080808080808080808080808476657262",
+            hex::encode(b.serialize())
+        );
+        assert_eq!(
+            "d757709f08f7a81da64a969200e59ff7e6cd6b06674c3f668ce151e84298aa79",
+            hex::encode(b.hash())
+        );
+    }
+}
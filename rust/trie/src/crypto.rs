@@ -0,0 +1,18 @@
+use tiny_keccak::Hasher;
+use tiny_keccak::Keccak;
+
+// // Keccak256 calculates and returns the Keccak256 hash of the input data.
+// func Keccak256(data ...[]byte) []byte {
+//   d := sha3.NewLegacyKeccak256()
+//   for _, b := range data {
+//     d.Write(b)
+//   }
+//   return d.Sum(nil)
+// }
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak::v256();
+    let mut hash = [0u8; 32];
+    hasher.update(data);
+    hasher.finalize(&mut hash);
+    hash
+}
@@ -0,0 +1,269 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+// NodeDb stores the RLP-encoded trie nodes keyed by their hash, like the Go
+// ProofDB. The methods take &self so that a database can be shared by several
+// tries, the implementations synchronize internally.
+pub trait NodeDb: fmt::Debug + Send + Sync {
+    // Put inserts the given value into the key-value data store.
+    fn put(&self, key: &[u8; 32], value: &[u8]) -> io::Result<()>;
+
+    // Delete removes the key from the key-value data store.
+    fn delete(&self, key: &[u8; 32]) -> io::Result<()>;
+
+    // Has retrieves if a key is present in the key-value data store.
+    fn has(&self, key: &[u8; 32]) -> io::Result<bool>;
+
+    // Get retrieves the given key if it's present in the key-value data store.
+    fn get(&self, key: &[u8; 32]) -> io::Result<Option<Vec<u8>>>;
+}
+
+// MemoryDb keeps the nodes in a HashMap.
+#[derive(Debug, Default)]
+pub struct MemoryDb {
+    kv: RwLock<HashMap<[u8; 32], Vec<u8>>>,
+}
+
+impl MemoryDb {
+    pub fn new() -> MemoryDb {
+        MemoryDb::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.kv.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl NodeDb for MemoryDb {
+    fn put(&self, key: &[u8; 32], value: &[u8]) -> io::Result<()> {
+        self.kv.write().unwrap().insert(*key, value.to_vec());
+        Ok(())
+    }
+
+    fn delete(&self, key: &[u8; 32]) -> io::Result<()> {
+        self.kv.write().unwrap().remove(key);
+        Ok(())
+    }
+
+    fn has(&self, key: &[u8; 32]) -> io::Result<bool> {
+        Ok(self.kv.read().unwrap().contains_key(key))
+    }
+
+    fn get(&self, key: &[u8; 32]) -> io::Result<Option<Vec<u8>>> {
+        Ok(self.kv.read().unwrap().get(key).cloned())
+    }
+}
+
+// FileDb keeps every node in its own file, named by the hex of its hash, in a
+// directory. A node is written to a temporary file first and renamed, so a
+// crash never leaves a truncated node behind.
+#[derive(Debug)]
+pub struct FileDb {
+    dir: PathBuf,
+}
+
+impl FileDb {
+    // open uses the directory, creating it if needed.
+    pub fn open<P: AsRef<Path>>(dir: P) -> io::Result<FileDb> {
+        fs::create_dir_all(&dir)?;
+        Ok(FileDb { dir: dir.as_ref().to_path_buf() })
+    }
+
+    fn path(&self, key: &[u8; 32]) -> PathBuf {
+        self.dir.join(hex::encode(key))
+    }
+}
+
+impl NodeDb for FileDb {
+    fn put(&self, key: &[u8; 32], value: &[u8]) -> io::Result<()> {
+        let path = self.path(key);
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, value)?;
+        fs::rename(&tmp, &path)
+    }
+
+    fn delete(&self, key: &[u8; 32]) -> io::Result<()> {
+        match fs::remove_file(self.path(key)) {
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+            result => result,
+        }
+    }
+
+    fn has(&self, key: &[u8; 32]) -> io::Result<bool> {
+        self.path(key).try_exists()
+    }
+
+    fn get(&self, key: &[u8; 32]) -> io::Result<Option<Vec<u8>>> {
+        match fs::read(self.path(key)) {
+            Ok(value) => Ok(Some(value)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trie::{Trie, TrieError};
+    use std::sync::Arc;
+
+    fn key(i: u32) -> Vec<u8> {
+        rlp::encode(&i).to_vec()
+    }
+
+    fn value(i: u32) -> Vec<u8> {
+        vec![i as u8; 1 + i as usize % 40]
+    }
+
+    fn check_db(db: &dyn NodeDb) {
+        let key = [1; 32];
+        assert!(!db.has(&key).unwrap());
+        assert_eq!(None, db.get(&key).unwrap());
+
+        db.put(&key, b"node").unwrap();
+        assert!(db.has(&key).unwrap());
+        assert_eq!(Some(b"node".to_vec()), db.get(&key).unwrap());
+
+        db.delete(&key).unwrap();
+        assert!(!db.has(&key).unwrap());
+        db.delete(&key).unwrap();
+    }
+
+    // check_commit_and_open commits a trie to db, reopens it and updates it.
+    fn check_commit_and_open(db: Arc<dyn NodeDb>) {
+        let mut tr = Trie::open(db.clone(), crate::EMPTY_NODE_HASH).unwrap();
+        let mut expected = Trie::new();
+        for i in 0..200 {
+            tr.put(&key(i), &value(i));
+            expected.put(&key(i), &value(i));
+        }
+        let root = tr.commit().unwrap();
+        assert_eq!(expected.hash(), root.to_vec());
+        assert_eq!(expected.hash(), tr.hash());
+
+        let mut tr = Trie::open(db.clone(), root).unwrap();
+        for i in 0..200 {
+            assert_eq!(Some(value(i)), tr.get(&key(i)));
+        }
+        assert_eq!(None, tr.get(&key(200)));
+        assert_eq!(expected.prove(&key(7)), tr.prove(&key(7)));
+        assert_eq!(expected.entries(), tr.entries());
+
+        for i in (0..200).step_by(3) {
+            assert!(tr.delete(&key(i)));
+            expected.delete(&key(i));
+        }
+        tr.put(&key(500), b"new");
+        expected.put(&key(500), b"new");
+        assert_eq!(expected.hash(), tr.hash());
+
+        let root = tr.commit().unwrap();
+        let tr = Trie::open(db, root).unwrap();
+        assert_eq!(expected.hash(), tr.hash());
+        assert_eq!(Some(b"new".to_vec()), tr.get(&key(500)));
+        assert_eq!(None, tr.get(&key(3)));
+        assert_eq!(Some(value(4)), tr.get(&key(4)));
+    }
+
+    #[test]
+    fn test_memory_db() {
+        check_db(&MemoryDb::new());
+        check_commit_and_open(Arc::new(MemoryDb::new()));
+    }
+
+    #[test]
+    fn test_file_db() {
+        let dir = tempfile::tempdir().unwrap();
+        check_db(&FileDb::open(dir.path()).unwrap());
+        check_commit_and_open(Arc::new(FileDb::open(dir.path()).unwrap()));
+    }
+
+    #[test]
+    fn test_file_db_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut tr = Trie::open(Arc::new(FileDb::open(dir.path()).unwrap()), crate::EMPTY_NODE_HASH).unwrap();
+        tr.put(b"hello", b"world");
+        let root = tr.commit().unwrap();
+
+        // a new FileDb on the same directory sees the committed nodes
+        let tr = Trie::open(Arc::new(FileDb::open(dir.path()).unwrap()), root).unwrap();
+        assert_eq!(Some(b"world".to_vec()), tr.get(b"hello"));
+    }
+
+    #[test]
+    fn test_commit_writes_changed_nodes() {
+        let db = Arc::new(MemoryDb::new());
+        let mut tr = Trie::open(db.clone(), crate::EMPTY_NODE_HASH).unwrap();
+        for i in 0..1000 {
+            tr.put(&key(i), &value(i));
+        }
+        let root = tr.commit().unwrap();
+        let stored = db.len();
+
+        // only the path of the updated key is written again
+        let mut tr = Trie::open(db.clone(), root).unwrap();
+        tr.put(&key(7), b"updated");
+        tr.commit().unwrap();
+        assert!(db.len() - stored <= 4, "{} new nodes", db.len() - stored);
+    }
+
+    #[test]
+    fn test_open_missing_root() {
+        let db = Arc::new(MemoryDb::new());
+        assert!(matches!(Trie::open(db.clone(), [1; 32]), Err(TrieError::MissingNode(hash)) if hash == [1; 32]));
+
+        let mut tr = Trie::open(db.clone(), crate::EMPTY_NODE_HASH).unwrap();
+        for i in 0..100 {
+            tr.put(&key(i), &value(i));
+        }
+        let root = tr.commit().unwrap();
+
+        // a node below the root is gone
+        let tr = Trie::open(db.clone(), root).unwrap();
+        let Some(proof) = tr.prove(&key(1)) else { panic!("key 1 is in the trie") };
+        let hash = crate::crypto::keccak256(&proof.nodes()[1]);
+        db.delete(&hash).unwrap();
+        assert!(matches!(tr.try_get(&key(1)), Err(TrieError::MissingNode(missing)) if missing == hash));
+        assert!(matches!(tr.try_prove(&key(1)), Err(TrieError::MissingNode(missing)) if missing == hash));
+        assert!(matches!(tr.try_prove_absence(&key(1)), Err(TrieError::MissingNode(missing)) if missing == hash));
+        assert!(matches!(tr.try_prove_many(&[&key(1)]), Err(TrieError::MissingNode(missing)) if missing == hash));
+    }
+
+    #[test]
+    fn test_delete_collapses_stored_sibling() {
+        // a branch with two stored leaves, neither of them embedded
+        let db = Arc::new(MemoryDb::new());
+        let mut tr = Trie::open(db.clone(), crate::EMPTY_NODE_HASH).unwrap();
+        tr.put(&[0x10], &[1; 40]);
+        tr.put(&[0x20], &[2; 40]);
+        let root = tr.commit().unwrap();
+
+        let mut expected = Trie::new();
+        expected.put(&[0x20], &[2; 40]);
+        let mut tr = Trie::open(db.clone(), root).unwrap();
+        assert!(matches!(tr.try_delete(&[0x10]), Ok(true)));
+        assert_eq!(expected.hash(), tr.hash());
+        let root = tr.commit().unwrap();
+        assert_eq!(Some(vec![2; 40]), Trie::open(db.clone(), root).unwrap().get(&[0x20]));
+
+        // the sibling cannot be loaded, the delete fails and changes nothing
+        let mut tr = Trie::open(db.clone(), root).unwrap();
+        tr.put(&[0x10], &[1; 40]);
+        let root = tr.commit().unwrap();
+        let Some(proof) = tr.prove(&[0x20]) else { panic!("0x20 is in the trie") };
+        let missing = crate::crypto::keccak256(&proof.nodes()[1]);
+        db.delete(&missing).unwrap();
+        let mut tr = Trie::open(db.clone(), root).unwrap();
+        assert!(matches!(tr.try_delete(&[0x10]), Err(TrieError::MissingNode(hash)) if hash == missing));
+        assert_eq!(root.to_vec(), tr.hash());
+    }
+}
@@ -0,0 +1,40 @@
+use crate::nodes::Node;
+
+// EmptyNodeRaw     = []byte{}
+pub static EMPTY_NODE_RAW: [u8; 0] = [];
+
+// EmptyNodeHash, _ = hex.DecodeString("56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421")
+pub static EMPTY_NODE_HASH: [u8; 32] = [
+	0x56, 0xe8, 0x1f, 0x17, 0x1b, 0xcc, 0x55, 0xa6, 0xff, 0x83, 0x45, 0xe6, 0x92, 0xc0, 0xf8, 0x6e,
+	0x5b, 0x48, 0xe0, 0x1b, 0x99, 0x6c, 0xad, 0xc0, 0x01, 0x62, 0x2f, 0xb5, 0xe3, 0x63, 0xb4, 0x21,
+];
+
+// func IsEmptyNode(node Node) bool {
+//   return node == nil
+// }
+pub fn is_empty_node(node: &Node) -> bool {
+    matches!(node, Node::Empty)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::keccak256;
+    use hex::FromHex;
+
+    // func TestEmptyNodeHash(t *testing.T) {
+    //   emptyRLP, err := rlp.EncodeToBytes(EmptyNodeRaw)
+    //   require.NoError(t, err)
+    //   require.Equal(t, EmptyNodeHash, Keccak256(emptyRLP))
+    // }
+    #[test]
+    fn test_empty_node_hash() {
+        let empty_node_hash: [u8; 32] = FromHex::from_hex("56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421").unwrap();
+        assert_eq!(empty_node_hash, EMPTY_NODE_HASH);
+
+        let empty_rlp = rlp::encode(&EMPTY_NODE_RAW.to_vec()).to_vec();
+        assert_eq!(empty_rlp, [0x80]);
+
+        assert_eq!(EMPTY_NODE_HASH, keccak256(&empty_rlp));
+    }
+}
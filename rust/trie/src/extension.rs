@@ -0,0 +1,128 @@
+use rlp::RlpStream;
+
+use crate::nibbles::Nibble;
+use crate::nodes::{Node, NodeCache};
+
+// type ExtensionNode struct {
+//   Path []Nibble
+//   Next Node
+// }
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtensionNode {
+    pub(crate) path: Vec<Nibble>,
+    pub(crate) next: Box<Node>,
+    pub(crate) cache: NodeCache,
+}
+
+impl ExtensionNode {
+    // func NewExtensionNode(nibbles []Nibble, next Node) *ExtensionNode {
+    //   return &ExtensionNode{
+    //     Path: nibbles,
+    //     Next: next,
+    //   }
+    // }
+    pub fn new(nibbles: Vec<Nibble>, next: Node) -> ExtensionNode {
+        ExtensionNode {
+            path: nibbles,
+            next: Box::new(next),
+            cache: NodeCache::default(),
+        }
+    }
+
+    // the fields are read-only outside of the crate, see NodeCache
+    pub fn path(&self) -> &[Nibble] {
+        &self.path
+    }
+
+    pub fn next(&self) -> &Node {
+        &self.next
+    }
+
+    // func (e ExtensionNode) Hash() []byte {
+    //   return crypto.Keccak256(e.Serialize())
+    // }
+    pub fn hash(&self) -> Vec<u8> {
+        self.cache.hash(|| self.encode()).to_vec()
+    }
+
+    // func (e ExtensionNode) Raw() []interface{} {
+    //   hashes := make([]interface{}, 2)
+    //   hashes[0] = ToBytes(ToPrefixed(e.Path, false))
+    //   if len(Serialize(e.Next)) >= 32 {
+    //     hashes[1] = e.Next.Hash()
+    //   } else {
+    //     hashes[1] = e.Next.Raw()
+    //   }
+    //   return hashes
+    // }
+    pub fn raw(&self, s: &mut RlpStream) {
+        let path = Nibble::to_bytes(Nibble::to_prefixed(self.path.clone(), false));
+        s.begin_list(2);
+        s.append(&path);
+        self.next.child_ref().append_to(s);
+    }
+
+    // func (e ExtensionNode) Serialize() []byte {
+    //   return Serialize(e)
+    // }
+    pub fn serialize(&self) -> Vec<u8> {
+        self.encoded().to_vec()
+    }
+
+    // encoded is Serialize without copying the cached encoding.
+    pub(crate) fn encoded(&self) -> &[u8] {
+        self.cache.encoded(|| self.encode())
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut s = RlpStream::new();
+        self.raw(&mut s);
+        s.out().to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::branch::BranchNode;
+    use crate::leaf::LeafNode;
+
+    // func TestExtensionNode(t *testing.T) {
+    //   nibbles, value := []byte{5, 0, 6}, []byte("coin")
+    //   leaf, err := NewLeafNodeFromNibbleBytes(nibbles, value)
+    //   require.NoError(t, err)
+    //
+    //   b := NewBranchNode()
+    //   b.SetBranch(0, leaf)
+    //   b.SetValue([]byte("verb")) // set the value for verb
+    //
+    //   ns, err := FromNibbleBytes([]byte{0, 1, 0, 2, 0, 3, 0, 4})
+    //   require.NoError(t, err)
+    //   e := NewExtensionNode(ns, b)
+    //   require.Equal(t, "e4850001020304ddc882350684636f696e8080808080808080808080808080808476657262", fmt.Sprintf("%x", e.Serialize()))
+    //   require.Equal(t, "64d67c5318a714d08de6958c0e63a05522642f3f1087c6fd68a97837f203d359", fmt.Sprintf("%x", e.Hash()))
+    // }
+    #[test]
+    fn test_extension_node() {
+        let nibbles: &[u8] = &[5, 0, 6];
+        let value: &[u8] = b"coin";
+
+        let leaf = LeafNode::new_from_nibble_bytes(nibbles, value).unwrap();
+
+        let mut b = BranchNode::new();
+        b.set_branch(Nibble(0), Node::Leaf(leaf));
+        b.set_value(b"verb".to_vec()); // set the value for verb
+
+        let ns = Nibble::from_nibble_bytes(vec![0, 1, 0, 2, 0, 3, 0, 4]).unwrap();
+        let e = ExtensionNode::new(ns, Node::Branch(Box::new(b)));
+
+        assert_eq!(
+            "e4850001020304ddc882350684636f696e8080808080808080808080808080808476657262",
+            hex::encode(e.serialize())
+        );
+        assert_eq!(
+            "64d67c5318a714d08de6958c0e63a05522642f3f1087c6fd68a97837f203d359",
+            hex::encode(e.hash())
+        );
+    }
+}
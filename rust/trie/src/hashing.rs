@@ -0,0 +1,188 @@
+use crate::stacktrie::StackTrie;
+use crate::trie::Trie;
+
+// // TrieHasher is the tool used to calculate the hash of derivable list.
+// // This is internal, do not use.
+// type TrieHasher interface {
+// 	Reset()
+// 	Update([]byte, []byte)
+// 	Hash() common.Hash
+// }
+pub trait TrieHasher {
+    fn reset(&mut self);
+    fn update(&mut self, key: &[u8], value: &[u8]);
+    fn hash(&self) -> Vec<u8>;
+}
+
+impl TrieHasher for StackTrie {
+    fn reset(&mut self) {
+        StackTrie::reset(self)
+    }
+
+    fn update(&mut self, key: &[u8], value: &[u8]) {
+        StackTrie::update(self, key, value).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    fn hash(&self) -> Vec<u8> {
+        StackTrie::hash(self)
+    }
+}
+
+impl TrieHasher for Trie {
+    fn reset(&mut self) {
+        *self = Trie::new();
+    }
+
+    fn update(&mut self, key: &[u8], value: &[u8]) {
+        self.put(key, value)
+    }
+
+    fn hash(&self) -> Vec<u8> {
+        Trie::hash(self)
+    }
+}
+
+// // DerivableList is the input to DeriveSha.
+// // It is implemented by the 'Transactions' and 'Receipts' types.
+// // This is internal, do not use these methods.
+// type DerivableList interface {
+// 	Len() int
+// 	EncodeIndex(int, *bytes.Buffer)
+// }
+pub trait DerivableList {
+    fn len(&self) -> usize;
+    fn encode_index(&self, i: usize, buf: &mut Vec<u8>);
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+// a list of values that are already encoded
+impl DerivableList for [Vec<u8>] {
+    fn len(&self) -> usize {
+        <[Vec<u8>]>::len(self)
+    }
+
+    fn encode_index(&self, i: usize, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&self[i]);
+    }
+}
+
+// func encodeForDerive(list DerivableList, i int, buf *bytes.Buffer) []byte {
+// 	buf.Reset()
+// 	list.EncodeIndex(i, buf)
+// 	// It's really unfortunate that we need to do perform this copy.
+// 	// StackTrie holds onto the values until Hash is called, so the values
+// 	// written to it must not alias.
+// 	return common.CopyBytes(buf.Bytes())
+// }
+fn encode_for_derive<L: DerivableList + ?Sized>(list: &L, i: usize, buf: &mut Vec<u8>) {
+    buf.clear();
+    list.encode_index(i, buf);
+}
+
+// DeriveSha creates the tree hashes of transactions and receipts in a block header.
+pub fn derive_sha<L: DerivableList + ?Sized>(list: &L, hasher: &mut dyn TrieHasher) -> Vec<u8> {
+    hasher.reset();
+
+    let mut value = Vec::new();
+    let mut update = |i: usize, hasher: &mut dyn TrieHasher| {
+        encode_for_derive(list, i, &mut value);
+        hasher.update(&rlp::encode(&(i as u64)), &value);
+    };
+
+    // StackTrie requires values to be inserted in increasing hash order, which is not the
+    // order that `list` provides hashes in. This insertion sequence ensures that the
+    // order is correct.
+    // for i := 1; i < list.Len() && i <= 0x7f; i++ {
+    for i in 1..list.len().min(0x80) {
+        update(i, hasher);
+    }
+    // if list.Len() > 0 {
+    if !list.is_empty() {
+        update(0, hasher);
+    }
+    // for i := 0x80; i < list.Len(); i++ {
+    for i in 0x80..list.len() {
+        update(i, hasher);
+    }
+    hasher.hash()
+}
+
+// derive_root returns the root of the list, the transactionsRoot or
+// receiptsRoot of a block, computed with a StackTrie.
+pub fn derive_root<L: DerivableList + ?Sized>(list: &L) -> Vec<u8> {
+    derive_sha(list, &mut StackTrie::new())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::empty::EMPTY_NODE_HASH;
+    use rlp::RlpStream;
+    use serde_json::Value;
+
+    // quantity decodes a hex quantity of the JSON-RPC API into its big-endian bytes.
+    fn quantity(s: &str) -> Vec<u8> {
+        let s = s.trim_start_matches("0x");
+        let bytes = hex::decode(if s.len() % 2 == 1 { format!("0{}", s) } else { s.to_string() }).unwrap();
+        bytes.into_iter().skip_while(|b| *b == 0).collect()
+    }
+
+    // legacy_transaction encodes a pre-EIP-2718 transaction of the fixtures.
+    fn legacy_transaction(tx: &Value) -> Vec<u8> {
+        let mut s = RlpStream::new_list(9);
+        s.append(&quantity(tx["nonce"].as_str().unwrap()));
+        s.append(&quantity(tx["gasPrice"].as_str().unwrap()));
+        s.append(&quantity(tx["gas"].as_str().unwrap()));
+        s.append(&hex::decode(tx["to"].as_str().unwrap_or("").trim_start_matches("0x")).unwrap());
+        s.append(&quantity(tx["value"].as_str().unwrap()));
+        s.append(&hex::decode(tx["input"].as_str().unwrap().trim_start_matches("0x")).unwrap());
+        s.append(&quantity(tx["v"].as_str().unwrap()));
+        s.append(&quantity(tx["r"].as_str().unwrap()));
+        s.append(&quantity(tx["s"].as_str().unwrap()));
+        s.out().to_vec()
+    }
+
+    #[test]
+    fn test_derive_root_matches_trie() {
+        for n in [0, 1, 2, 0x7f, 0x80, 0x81, 300] {
+            let list: Vec<Vec<u8>> = (0..n).map(|i| vec![i as u8; 1 + i % 40]).collect();
+
+            let mut tr = Trie::new();
+            for (i, value) in list.iter().enumerate() {
+                tr.put(&rlp::encode(&(i as u64)), value);
+            }
+            assert_eq!(tr.hash(), derive_root(list.as_slice()), "{} items", n);
+            assert_eq!(tr.hash(), derive_sha(list.as_slice(), &mut Trie::new()), "{} items", n);
+        }
+
+        let empty: &[Vec<u8>] = &[];
+        assert_eq!(EMPTY_NODE_HASH.to_vec(), derive_root(empty));
+    }
+
+    #[test]
+    fn test_derive_root_reuses_hasher() {
+        let first: Vec<Vec<u8>> = (0..200).map(|i| vec![i as u8; 20]).collect();
+        let second: Vec<Vec<u8>> = (0..100).map(|i| vec![i as u8; 30]).collect();
+
+        let mut hasher = StackTrie::new();
+        derive_sha(first.as_slice(), &mut hasher);
+        assert_eq!(derive_root(second.as_slice()), derive_sha(second.as_slice(), &mut hasher));
+    }
+
+    #[test]
+    fn test_derive_root_block_10467135() {
+        let txs: Vec<Value> =
+            serde_json::from_str(include_str!("../../../transactions_from_block_10467135.json")).unwrap();
+        let list: Vec<Vec<u8>> = txs.iter().map(legacy_transaction).collect();
+        assert_eq!(193, list.len());
+
+        // the transaction root for block 10467135
+        assert_eq!(
+            "bb345e208bda953c908027a45aa443d6cab6b8d2fd64e83ec52f1008ddeafa58",
+            hex::encode(derive_root(list.as_slice()))
+        );
+    }
+}
@@ -0,0 +1,343 @@
+use rlp::{Decodable, DecoderError, Encodable, Rlp, RlpStream};
+use serde::Deserialize;
+
+use crate::crypto::keccak256;
+use crate::hexutil;
+use crate::receipt::Bloom;
+use crate::transaction::{decode_big, decode_fixed};
+
+// type Header struct {
+// 	ParentHash  common.Hash    `json:"parentHash"       gencodec:"required"`
+// 	UncleHash   common.Hash    `json:"sha3Uncles"       gencodec:"required"`
+// 	Coinbase    common.Address `json:"miner"`
+// 	Root        common.Hash    `json:"stateRoot"        gencodec:"required"`
+// 	TxHash      common.Hash    `json:"transactionsRoot" gencodec:"required"`
+// 	ReceiptHash common.Hash    `json:"receiptsRoot"     gencodec:"required"`
+// 	Bloom       Bloom          `json:"logsBloom"        gencodec:"required"`
+// 	Difficulty  *big.Int       `json:"difficulty"       gencodec:"required"`
+// 	Number      *big.Int       `json:"number"           gencodec:"required"`
+// 	GasLimit    uint64         `json:"gasLimit"         gencodec:"required"`
+// 	GasUsed     uint64         `json:"gasUsed"          gencodec:"required"`
+// 	Time        uint64         `json:"timestamp"        gencodec:"required"`
+// 	Extra       []byte         `json:"extraData"        gencodec:"required"`
+// 	MixDigest   common.Hash    `json:"mixHash"`
+// 	Nonce       BlockNonce     `json:"nonce"`
+//
+// 	// BaseFee was added by EIP-1559 and is ignored in legacy headers.
+// 	BaseFee *big.Int `json:"baseFeePerGas" rlp:"optional"`
+//
+// 	// WithdrawalsHash was added by EIP-4895 and is ignored in legacy headers.
+// 	WithdrawalsHash *common.Hash `json:"withdrawalsRoot" rlp:"optional"`
+//
+// 	// BlobGasUsed was added by EIP-4844 and is ignored in legacy headers.
+// 	BlobGasUsed *uint64 `json:"blobGasUsed" rlp:"optional"`
+//
+// 	// ExcessBlobGas was added by EIP-4844 and is ignored in legacy headers.
+// 	ExcessBlobGas *uint64 `json:"excessBlobGas" rlp:"optional"`
+//
+// 	// ParentBeaconRoot was added by EIP-4788 and is ignored in legacy headers.
+// 	ParentBeaconRoot *common.Hash `json:"parentBeaconBlockRoot" rlp:"optional"`
+//
+// 	// RequestsHash was added by EIP-7685 and is ignored in legacy headers.
+// 	RequestsHash *common.Hash `json:"requestsHash" rlp:"optional"`
+// }
+//
+// The header of go-ethereum, which the Go code of this project does not have.
+// The fields from BaseFee on are None before the fork that added them. Like
+// the rlp:"optional" tag, the trailing None fields are left out of the
+// encoding, and a None followed by a later field is encoded as zero.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Header {
+    #[serde(deserialize_with = "hexutil::fixed")]
+    pub parent_hash: [u8; 32],
+    #[serde(rename = "sha3Uncles", deserialize_with = "hexutil::fixed")]
+    pub uncle_hash: [u8; 32],
+    #[serde(rename = "miner", deserialize_with = "hexutil::fixed")]
+    pub coinbase: [u8; 20],
+    #[serde(rename = "stateRoot", deserialize_with = "hexutil::fixed")]
+    pub root: [u8; 32],
+    #[serde(rename = "transactionsRoot", deserialize_with = "hexutil::fixed")]
+    pub tx_hash: [u8; 32],
+    #[serde(rename = "receiptsRoot", deserialize_with = "hexutil::fixed")]
+    pub receipt_hash: [u8; 32],
+    #[serde(rename = "logsBloom")]
+    pub bloom: Bloom,
+    #[serde(deserialize_with = "hexutil::big")]
+    pub difficulty: Vec<u8>,
+    #[serde(deserialize_with = "hexutil::big")]
+    pub number: Vec<u8>,
+    #[serde(deserialize_with = "hexutil::uint64")]
+    pub gas_limit: u64,
+    #[serde(deserialize_with = "hexutil::uint64")]
+    pub gas_used: u64,
+    #[serde(rename = "timestamp", deserialize_with = "hexutil::uint64")]
+    pub time: u64,
+    #[serde(rename = "extraData", deserialize_with = "hexutil::bytes")]
+    pub extra: Vec<u8>,
+    #[serde(rename = "mixHash", deserialize_with = "hexutil::fixed")]
+    pub mix_digest: [u8; 32],
+    #[serde(deserialize_with = "hexutil::fixed")]
+    pub nonce: [u8; 8],
+
+    // London (EIP-1559)
+    #[serde(rename = "baseFeePerGas", default, deserialize_with = "hexutil::opt_big")]
+    pub base_fee: Option<Vec<u8>>,
+    // Shanghai (EIP-4895)
+    #[serde(rename = "withdrawalsRoot", default, deserialize_with = "hexutil::opt_fixed")]
+    pub withdrawals_hash: Option<[u8; 32]>,
+    // Cancun (EIP-4844 and EIP-4788)
+    #[serde(default, deserialize_with = "hexutil::opt_uint64")]
+    pub blob_gas_used: Option<u64>,
+    #[serde(default, deserialize_with = "hexutil::opt_uint64")]
+    pub excess_blob_gas: Option<u64>,
+    #[serde(rename = "parentBeaconBlockRoot", default, deserialize_with = "hexutil::opt_fixed")]
+    pub parent_beacon_root: Option<[u8; 32]>,
+    // Prague (EIP-7685)
+    #[serde(default, deserialize_with = "hexutil::opt_fixed")]
+    pub requests_hash: Option<[u8; 32]>,
+}
+
+// the number of fields up to MixDigest and Nonce, and of the optional ones
+const REQUIRED_FIELDS: usize = 15;
+const OPTIONAL_FIELDS: usize = 6;
+
+impl Header {
+    // Hash returns the block hash of the header, which is simply the keccak256
+    // hash of its RLP encoding.
+    pub fn hash(&self) -> [u8; 32] {
+        keccak256(&rlp::encode(self))
+    }
+
+    // optional_fields returns the number of optional fields that are encoded,
+    // up to the last one that is set.
+    fn optional_fields(&self) -> usize {
+        let set = [
+            self.base_fee.is_some(),
+            self.withdrawals_hash.is_some(),
+            self.blob_gas_used.is_some(),
+            self.excess_blob_gas.is_some(),
+            self.parent_beacon_root.is_some(),
+            self.requests_hash.is_some(),
+        ];
+        set.iter().rposition(|set| *set).map_or(0, |i| i + 1)
+    }
+}
+
+impl Encodable for Header {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        let optional = self.optional_fields();
+        s.begin_list(REQUIRED_FIELDS + optional);
+        s.append(&&self.parent_hash[..]);
+        s.append(&&self.uncle_hash[..]);
+        s.append(&&self.coinbase[..]);
+        s.append(&&self.root[..]);
+        s.append(&&self.tx_hash[..]);
+        s.append(&&self.receipt_hash[..]);
+        s.append(&self.bloom);
+        s.append(&self.difficulty);
+        s.append(&self.number);
+        s.append(&self.gas_limit);
+        s.append(&self.gas_used);
+        s.append(&self.time);
+        s.append(&self.extra);
+        s.append(&&self.mix_digest[..]);
+        s.append(&&self.nonce[..]);
+
+        let zero = [0; 32];
+        if optional > 0 {
+            s.append(&self.base_fee.as_deref().unwrap_or_default());
+        }
+        if optional > 1 {
+            s.append(&&self.withdrawals_hash.as_ref().unwrap_or(&zero)[..]);
+        }
+        if optional > 2 {
+            s.append(&self.blob_gas_used.unwrap_or_default());
+        }
+        if optional > 3 {
+            s.append(&self.excess_blob_gas.unwrap_or_default());
+        }
+        if optional > 4 {
+            s.append(&&self.parent_beacon_root.as_ref().unwrap_or(&zero)[..]);
+        }
+        if optional > 5 {
+            s.append(&&self.requests_hash.as_ref().unwrap_or(&zero)[..]);
+        }
+    }
+}
+
+impl Decodable for Header {
+    fn decode(rlp: &Rlp) -> Result<Header, DecoderError> {
+        // the header list has to span all of the input
+        if rlp.payload_info()?.total() != rlp.as_raw().len() {
+            return Err(DecoderError::RlpIsTooBig);
+        }
+        let count = rlp.item_count()?;
+        if !(REQUIRED_FIELDS..=REQUIRED_FIELDS + OPTIONAL_FIELDS).contains(&count) {
+            return Err(DecoderError::RlpIncorrectListLen);
+        }
+        // a missing optional field is None
+        let at = |i: usize| if i < count { rlp.at(i).map(Some) } else { Ok(None) };
+        Ok(Header {
+            parent_hash: decode_fixed(&rlp.at(0)?)?,
+            uncle_hash: decode_fixed(&rlp.at(1)?)?,
+            coinbase: decode_fixed(&rlp.at(2)?)?,
+            root: decode_fixed(&rlp.at(3)?)?,
+            tx_hash: decode_fixed(&rlp.at(4)?)?,
+            receipt_hash: decode_fixed(&rlp.at(5)?)?,
+            bloom: rlp.val_at(6)?,
+            difficulty: decode_big(&rlp.at(7)?)?,
+            number: decode_big(&rlp.at(8)?)?,
+            gas_limit: rlp.val_at(9)?,
+            gas_used: rlp.val_at(10)?,
+            time: rlp.val_at(11)?,
+            extra: rlp.val_at(12)?,
+            mix_digest: decode_fixed(&rlp.at(13)?)?,
+            nonce: decode_fixed(&rlp.at(14)?)?,
+            base_fee: at(15)?.map(|r| decode_big(&r)).transpose()?,
+            withdrawals_hash: at(16)?.map(|r| decode_fixed(&r)).transpose()?,
+            blob_gas_used: at(17)?.map(|r| r.as_val()).transpose()?,
+            excess_blob_gas: at(18)?.map(|r| r.as_val()).transpose()?,
+            parent_beacon_root: at(19)?.map(|r| decode_fixed(&r)).transpose()?,
+            requests_hash: at(20)?.map(|r| decode_fixed(&r)).transpose()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::empty::EMPTY_NODE_HASH;
+
+    // the mainnet genesis block and block 1, with their hashes
+    const GENESIS: &str = r#"{
+        "hash": "0xd4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3",
+        "parentHash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+        "sha3Uncles": "0x1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347",
+        "miner": "0x0000000000000000000000000000000000000000",
+        "stateRoot": "0xd7f8974fb5ac78d9ac099b9ad5018bedc2ce0a72dad1827a1709da30580f0544",
+        "transactionsRoot": "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421",
+        "receiptsRoot": "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421",
+        "difficulty": "0x400000000",
+        "number": "0x0",
+        "gasLimit": "0x1388",
+        "gasUsed": "0x0",
+        "timestamp": "0x0",
+        "extraData": "0x11bbe8db4e347b4e8c937c1c8370e4b5ed33adb3db69cbdb7a38e1e50b1b82fa",
+        "mixHash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+        "nonce": "0x0000000000000042",
+        "logsBloom": "0xBLOOM"
+    }"#;
+
+    const BLOCK_1: &str = r#"{
+        "hash": "0x88e96d4537bea4d9c05d12549907b32561d3bf31f45aae734cdc119f13406cb6",
+        "parentHash": "0xd4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3",
+        "sha3Uncles": "0x1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347",
+        "miner": "0x05a56e2d52c817161883f50c441c3228cfe54d9f",
+        "stateRoot": "0xd67e4d450343046425ae4271474353857ab860dbc0a1dde64b41b5cd3a532bf3",
+        "transactionsRoot": "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421",
+        "receiptsRoot": "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421",
+        "difficulty": "0x3ff800000",
+        "number": "0x1",
+        "gasLimit": "0x1388",
+        "gasUsed": "0x0",
+        "timestamp": "0x55ba4224",
+        "extraData": "0x476574682f76312e302e302f6c696e75782f676f312e342e32",
+        "mixHash": "0x969b900de27b6ac6a67742365dd65f55a0526c41fd18e1b16f1a1215c2e66f59",
+        "nonce": "0x539bd4979fef1ec4",
+        "logsBloom": "0xBLOOM"
+    }"#;
+
+    fn from_json(json: &str) -> (Header, [u8; 32]) {
+        let json = json.replace("0xBLOOM", &hexutil::encode(&[0; 256]));
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let hash = hexutil::decode_fixed(value["hash"].as_str().unwrap()).unwrap();
+        (serde_json::from_value(value).unwrap(), hash)
+    }
+
+    // header returns a header with the optional fields of the first forks set.
+    fn header(optional: usize) -> Header {
+        Header {
+            parent_hash: [0x01; 32],
+            uncle_hash: [0x02; 32],
+            coinbase: [0x03; 20],
+            root: [0x04; 32],
+            tx_hash: EMPTY_NODE_HASH,
+            receipt_hash: EMPTY_NODE_HASH,
+            number: vec![0x01, 0x5a, 0x3e, 0x10],
+            gas_limit: 36_000_000,
+            gas_used: 21_000,
+            time: 0x6800_0000,
+            extra: b"extra".to_vec(),
+            base_fee: Some(vec![0x3b, 0x9a, 0xca, 0x00]).filter(|_| optional > 0),
+            withdrawals_hash: Some(EMPTY_NODE_HASH).filter(|_| optional > 1),
+            blob_gas_used: Some(0x20000).filter(|_| optional > 2),
+            excess_blob_gas: Some(0).filter(|_| optional > 3),
+            parent_beacon_root: Some([0x05; 32]).filter(|_| optional > 4),
+            requests_hash: Some([0x06; 32]).filter(|_| optional > 5),
+            ..Header::default()
+        }
+    }
+
+    #[test]
+    fn test_header_hash() {
+        let (genesis, hash) = from_json(GENESIS);
+        assert_eq!(hash, genesis.hash());
+
+        let (block_1, hash) = from_json(BLOCK_1);
+        assert_eq!(hash, block_1.hash());
+        assert_eq!(genesis.hash(), block_1.parent_hash);
+        assert_eq!(None, block_1.base_fee);
+        assert_eq!(REQUIRED_FIELDS, Rlp::new(&rlp::encode(&block_1)).item_count().unwrap());
+    }
+
+    #[test]
+    fn test_header_forks() {
+        for (fork, optional) in [("frontier", 0), ("london", 1), ("shanghai", 2), ("cancun", 5), ("prague", 6)] {
+            let header = header(optional);
+            let b = rlp::encode(&header);
+            assert_eq!(REQUIRED_FIELDS + optional, Rlp::new(&b).item_count().unwrap(), "{}", fork);
+            assert_eq!(header, rlp::decode::<Header>(&b).unwrap(), "{}", fork);
+            assert_eq!(keccak256(&b), header.hash(), "{}", fork);
+        }
+
+        // a None before a field that is set is encoded as zero
+        let header = Header { base_fee: None, ..header(2) };
+        let decoded = rlp::decode::<Header>(&rlp::encode(&header)).unwrap();
+        assert_eq!(Some(vec![]), decoded.base_fee);
+        assert_eq!(header.hash(), decoded.hash());
+    }
+
+    #[test]
+    fn test_header_decode_invalid() {
+        let b = rlp::encode(&header(OPTIONAL_FIELDS));
+        let rlp = Rlp::new(&b);
+
+        let mut s = RlpStream::new_list(REQUIRED_FIELDS - 1);
+        for item in rlp.iter().take(REQUIRED_FIELDS - 1) {
+            s.append_raw(item.as_raw(), 1);
+        }
+        assert_eq!(Err(DecoderError::RlpIncorrectListLen), rlp::decode::<Header>(&s.out()));
+
+        let mut s = RlpStream::new_list(REQUIRED_FIELDS + OPTIONAL_FIELDS + 1);
+        for item in rlp.iter() {
+            s.append_raw(item.as_raw(), 1);
+        }
+        s.append_empty_data();
+        assert_eq!(Err(DecoderError::RlpIncorrectListLen), rlp::decode::<Header>(&s.out()));
+
+        // a parent hash that is too short
+        let mut s = RlpStream::new_list(REQUIRED_FIELDS);
+        s.append(&&[0x01; 31][..]);
+        for item in rlp.iter().skip(1).take(REQUIRED_FIELDS - 1) {
+            s.append_raw(item.as_raw(), 1);
+        }
+        assert_eq!(Err(DecoderError::RlpIsTooShort), rlp::decode::<Header>(&s.out()));
+
+        // bytes after the header list
+        let mut trailing = b.to_vec();
+        trailing.push(0x80);
+        assert_eq!(Err(DecoderError::RlpIsTooBig), rlp::decode::<Header>(&trailing));
+        assert!(rlp::decode::<Header>(&b).is_ok());
+    }
+}
@@ -0,0 +1,189 @@
+use std::error::Error;
+use std::fmt;
+
+use serde::de::{self, Deserialize, Deserializer};
+
+// HexError is returned for hex strings of the JSON-RPC API that cannot be
+// decoded, with the messages of the Go hexutil errors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HexError {
+    EmptyString,
+    Syntax,
+    MissingPrefix,
+    OddLength,
+    EmptyNumber,
+    LeadingZero,
+    Uint64Range,
+    Big256Range,
+    // the decoded length and the length of the fixed size value
+    Length(usize, usize),
+}
+
+impl fmt::Display for HexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HexError::EmptyString => write!(f, "empty hex string"),
+            HexError::Syntax => write!(f, "invalid hex string"),
+            HexError::MissingPrefix => write!(f, "hex string without 0x prefix"),
+            HexError::OddLength => write!(f, "hex string of odd length"),
+            HexError::EmptyNumber => write!(f, "hex string \"0x\""),
+            HexError::LeadingZero => write!(f, "hex number with leading zero digits"),
+            HexError::Uint64Range => write!(f, "hex number > 64 bits"),
+            HexError::Big256Range => write!(f, "hex number > 256 bits"),
+            HexError::Length(got, want) => write!(f, "hex string has length {}, want {}", got * 2, want * 2),
+        }
+    }
+}
+
+impl Error for HexError {}
+
+// Decode decodes a hex string with 0x prefix.
+pub fn decode(input: &str) -> Result<Vec<u8>, HexError> {
+    if input.is_empty() {
+        return Err(HexError::EmptyString);
+    }
+    if !has_0x_prefix(input) {
+        return Err(HexError::MissingPrefix);
+    }
+    hex::decode(&input[2..]).map_err(|err| match err {
+        hex::FromHexError::OddLength => HexError::OddLength,
+        _ => HexError::Syntax,
+    })
+}
+
+// decode_fixed decodes a hex string with 0x prefix into a value of exactly N
+// bytes, like an address or a hash.
+pub fn decode_fixed<const N: usize>(input: &str) -> Result<[u8; N], HexError> {
+    let b = decode(input)?;
+    b.as_slice().try_into().map_err(|_| HexError::Length(b.len(), N))
+}
+
+// Encode encodes b as a hex string with 0x prefix.
+pub fn encode(b: &[u8]) -> String {
+    format!("0x{}", hex::encode(b))
+}
+
+// DecodeUint64 decodes a hex string with 0x prefix as a quantity.
+pub fn decode_uint64(input: &str) -> Result<u64, HexError> {
+    let raw = check_number(input)?;
+    if raw.len() > 16 {
+        return Err(HexError::Uint64Range);
+    }
+    u64::from_str_radix(raw, 16).map_err(|_| HexError::Syntax)
+}
+
+// EncodeUint64 encodes i as a hex string with 0x prefix.
+pub fn encode_uint64(i: u64) -> String {
+    format!("0x{:x}", i)
+}
+
+// DecodeBig decodes a hex string with 0x prefix as a quantity.
+// Numbers larger than 256 bits are not accepted.
+// The number is returned as big-endian bytes without leading zeros, the form
+// RLP encodes integers in.
+pub fn decode_big(input: &str) -> Result<Vec<u8>, HexError> {
+    let raw = check_number(input)?;
+    if raw.len() > 64 {
+        return Err(HexError::Big256Range);
+    }
+    let padded = if raw.len() % 2 == 1 { format!("0{}", raw) } else { raw.to_string() };
+    let b = hex::decode(padded).map_err(|_| HexError::Syntax)?;
+    Ok(b.into_iter().skip_while(|b| *b == 0).collect())
+}
+
+fn has_0x_prefix(input: &str) -> bool {
+    input.starts_with("0x") || input.starts_with("0X")
+}
+
+fn check_number(input: &str) -> Result<&str, HexError> {
+    if input.is_empty() {
+        return Err(HexError::EmptyString);
+    }
+    if !has_0x_prefix(input) {
+        return Err(HexError::MissingPrefix);
+    }
+    let input = &input[2..];
+    if input.is_empty() {
+        return Err(HexError::EmptyNumber);
+    }
+    if input.len() > 1 && input.starts_with('0') {
+        return Err(HexError::LeadingZero);
+    }
+    Ok(input)
+}
+
+// The functions below deserialize the JSON strings of the API with
+// #[serde(deserialize_with = "...")], like the Go common.Uint64, common.Bytes
+// and common.Big types.
+
+pub(crate) fn uint64<'de, D: Deserializer<'de>>(d: D) -> Result<u64, D::Error> {
+    decode_uint64(&String::deserialize(d)?).map_err(de::Error::custom)
+}
+
+pub(crate) fn opt_uint64<'de, D: Deserializer<'de>>(d: D) -> Result<Option<u64>, D::Error> {
+    Option::<String>::deserialize(d)?.map(|s| decode_uint64(&s).map_err(de::Error::custom)).transpose()
+}
+
+pub(crate) fn big<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<u8>, D::Error> {
+    decode_big(&String::deserialize(d)?).map_err(de::Error::custom)
+}
+
+pub(crate) fn opt_big<'de, D: Deserializer<'de>>(d: D) -> Result<Option<Vec<u8>>, D::Error> {
+    Option::<String>::deserialize(d)?.map(|s| decode_big(&s).map_err(de::Error::custom)).transpose()
+}
+
+pub(crate) fn bytes<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<u8>, D::Error> {
+    decode(&String::deserialize(d)?).map_err(de::Error::custom)
+}
+
+pub(crate) fn fixed<'de, D: Deserializer<'de>, const N: usize>(d: D) -> Result<[u8; N], D::Error> {
+    decode_fixed(&String::deserialize(d)?).map_err(de::Error::custom)
+}
+
+pub(crate) fn opt_fixed<'de, D: Deserializer<'de>, const N: usize>(d: D) -> Result<Option<[u8; N]>, D::Error> {
+    Option::<String>::deserialize(d)?.map(|s| decode_fixed(&s).map_err(de::Error::custom)).transpose()
+}
+
+pub(crate) fn fixed_list<'de, D: Deserializer<'de>, const N: usize>(d: D) -> Result<Vec<[u8; N]>, D::Error> {
+    Vec::<String>::deserialize(d)?.iter().map(|s| decode_fixed(s).map_err(de::Error::custom)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(Ok(vec![]), decode("0x"));
+        assert_eq!(Ok(vec![0x01, 0xab]), decode("0x01AB"));
+        assert_eq!(Err(HexError::EmptyString), decode(""));
+        assert_eq!(Err(HexError::MissingPrefix), decode("01"));
+        assert_eq!(Err(HexError::OddLength), decode("0x123"));
+        assert_eq!(Err(HexError::Syntax), decode("0xzz"));
+
+        assert_eq!(Ok([0xab; 2]), decode_fixed::<2>("0xabab"));
+        assert_eq!(Err(HexError::Length(1, 2)), decode_fixed::<2>("0xab"));
+        assert_eq!("0x01ab", encode(&[0x01, 0xab]));
+    }
+
+    #[test]
+    fn test_decode_uint64() {
+        assert_eq!(Ok(0), decode_uint64("0x0"));
+        assert_eq!(Ok(0x1bf52), decode_uint64("0x1bf52"));
+        assert_eq!(Ok(u64::MAX), decode_uint64("0xffffffffffffffff"));
+        assert_eq!(Err(HexError::Uint64Range), decode_uint64("0x10000000000000000"));
+        assert_eq!(Err(HexError::EmptyNumber), decode_uint64("0x"));
+        assert_eq!(Err(HexError::LeadingZero), decode_uint64("0x01"));
+        assert_eq!(Err(HexError::Syntax), decode_uint64("0xg"));
+        assert_eq!("0x1bf52", encode_uint64(0x1bf52));
+    }
+
+    #[test]
+    fn test_decode_big() {
+        assert_eq!(Ok(vec![]), decode_big("0x0"));
+        assert_eq!(Ok(vec![0x01, 0x1f, 0xe0]), decode_big("0x11fe0"));
+        assert_eq!(Ok(vec![0xff; 32]), decode_big(&format!("0x{}", "f".repeat(64))));
+        assert_eq!(Err(HexError::Big256Range), decode_big(&format!("0x1{}", "0".repeat(64))));
+        assert_eq!(Err(HexError::LeadingZero), decode_big("0x00"));
+    }
+}
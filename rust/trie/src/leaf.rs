@@ -0,0 +1,249 @@
+use rlp::RlpStream;
+
+use crate::nibbles::Nibble;
+use crate::nodes::NodeCache;
+
+// type LeafNode struct {
+//   Path  []Nibble
+//   Value []byte
+// }
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LeafNode {
+    pub(crate) path: Vec<Nibble>,
+    pub(crate) value: Vec<u8>,
+    pub(crate) cache: NodeCache,
+}
+
+impl LeafNode {
+    // func NewLeafNodeFromNibbleBytes(nibbles []byte, value []byte) (*LeafNode, error) {
+    //   ns, err := FromNibbleBytes(nibbles)
+    //   if err != nil {
+    //     return nil, fmt.Errorf("could not leaf node from nibbles: %w", err)
+    //   }
+    //
+    //   return NewLeafNodeFromNibbles(ns, value), nil
+    // }
+    pub fn new_from_nibble_bytes(nibbles: &[u8], value: &[u8]) -> Result<LeafNode, &'static str> {
+        let ns = Nibble::from_nibble_bytes(nibbles.to_vec())?;
+        Ok(LeafNode::new_leaf_node_from_nibbles(ns, value.to_vec()))
+    }
+
+    // func NewLeafNodeFromNibbles(nibbles []Nibble, value []byte) *LeafNode {
+    //   return &LeafNode{
+    //     Path:  nibbles,
+    //     Value: value,
+    //   }
+    // }
+    pub fn new_leaf_node_from_nibbles(nibbles: Vec<Nibble>, value: Vec<u8>) -> LeafNode {
+        LeafNode {
+            path: nibbles,
+            value,
+            cache: NodeCache::default(),
+        }
+    }
+
+    // func NewLeafNodeFromKeyValue(key, value string) *LeafNode {
+    //   return NewLeafNodeFromBytes([]byte(key), []byte(value))
+    // }
+    pub fn new_from_key_value(key: &str, value: &str) -> LeafNode {
+        LeafNode::new_from_bytes(key.as_bytes(), value.as_bytes())
+    }
+
+    // func NewLeafNodeFromBytes(key, value []byte) *LeafNode {
+    //   return NewLeafNodeFromNibbles(FromBytes(key), value)
+    // }
+    pub fn new_from_bytes(key: &[u8], value: &[u8]) -> LeafNode {
+        LeafNode::new_leaf_node_from_nibbles(Nibble::from_bytes(key.to_vec()), value.to_vec())
+    }
+
+    // the fields are read-only outside of the crate, see NodeCache
+    pub fn path(&self) -> &[Nibble] {
+        &self.path
+    }
+
+    pub fn value(&self) -> &[u8] {
+        &self.value
+    }
+
+    // func (l LeafNode) Hash() []byte {
+    //   return crypto.Keccak256(l.Serialize())
+    // }
+    pub fn hash(&self) -> Vec<u8> {
+        self.cache.hash(|| self.encode()).to_vec()
+    }
+
+    // func (l LeafNode) Raw() []interface{} {
+    //   path := ToBytes(ToPrefixed(l.Path, true))
+    //   raw := []interface{}{path, l.Value}
+    //   return raw
+    // }
+    pub fn raw(&self, s: &mut RlpStream) {
+        let path = Nibble::to_bytes(Nibble::to_prefixed(self.path.clone(), true));
+        s.begin_list(2);
+        s.append(&path);
+        s.append(&self.value);
+    }
+
+    // func (l LeafNode) Serialize() []byte {
+    //   return Serialize(l)
+    // }
+    pub fn serialize(&self) -> Vec<u8> {
+        self.encoded().to_vec()
+    }
+
+    // encoded is Serialize without copying the cached encoding.
+    pub(crate) fn encoded(&self) -> &[u8] {
+        self.cache.encoded(|| self.encode())
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut s = RlpStream::new();
+        self.raw(&mut s);
+        s.out().to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::keccak256;
+    use crate::nibbles::encode_nibbles;
+
+    // func printEachCalculationSteps(key, value []byte, isLeaf bool) map[string]string {
+    //   hexs := make(map[string]string)
+    //   hexs["key in nibbles"] = fmt.Sprintf("%x", FromBytes(key))
+    //   hexs["key in nibbles, and prefixed"] = fmt.Sprintf("%x", ToPrefixed(FromBytes(key), isLeaf))
+    //   hexs["key in nibbles, and prefixed, and convert back to buffer"] =
+    //     fmt.Sprintf("%x", ToBytes(ToPrefixed(FromBytes(key), isLeaf)))
+    //   beforeRLP := [][]byte{ToBytes(ToPrefixed(FromBytes(key), isLeaf)), value}
+    //   hexs["beforeRLP"] = fmt.Sprintf("%x", beforeRLP)
+    //   afterRLP, err := rlp.EncodeToBytes(beforeRLP)
+    //   if err != nil {
+    //     panic(err)
+    //   }
+    //   hexs["afterRLP"] = fmt.Sprintf("%x", afterRLP)
+    //   hexs["hash"] = fmt.Sprintf("%x", crypto.Keccak256(afterRLP))
+    //   return hexs
+    // }
+    fn print_each_calculation_steps(key: &[u8], value: &[u8], is_leaf: bool) -> Vec<(String, String)> {
+        let mut hexs: Vec<(String, String)> = Vec::new();
+        hexs.push(("key in nibbles".to_owned(), encode_nibbles(&Nibble::from_bytes(key.to_vec()))));
+        hexs.push(("key in nibbles, and prefixed".to_owned(), encode_nibbles(&Nibble::to_prefixed(Nibble::from_bytes(key.to_vec()), is_leaf))));
+        hexs.push(("key in nibbles, and prefixed, and convert back to buffer".to_owned(), hex::encode(Nibble::to_bytes(Nibble::to_prefixed(Nibble::from_bytes(key.to_vec()), is_leaf)))));
+        let before_rlp = vec![Nibble::to_bytes(Nibble::to_prefixed(Nibble::from_bytes(key.to_vec()), is_leaf)), value.to_vec()];
+        hexs.push(("beforeRLP".to_owned(), format!("{}{}", hex::encode(&before_rlp[0]), hex::encode(&before_rlp[1]))));
+        let after_rlp = rlp::encode_list::<Vec<u8>, _>(&before_rlp).to_vec();
+        hexs.push(("afterRLP".to_owned(), hex::encode(&after_rlp)));
+        hexs.push(("hash".to_owned(), hex::encode(keccak256(&after_rlp))));
+        hexs
+    }
+
+    // func TestLeafHash(t *testing.T) {
+    //   require.Equal(t, "01020304", fmt.Sprintf("%x", []byte{1, 2, 3, 4}))
+    //   require.Equal(t, "76657262", fmt.Sprintf("%x", []byte("verb")))
+    //
+    //   // "buffer to nibbles
+    //   require.Equal(t, "0001000200030004", fmt.Sprintf("%x", FromBytes([]byte{1, 2, 3, 4})))
+    //
+    //   // ToPrefixed
+    //   require.Equal(t, "02000001000200030004", fmt.Sprintf("%x", ToPrefixed(FromBytes([]byte{1, 2, 3, 4}), true)))
+    //
+    //   // ToBuffer
+    //   require.Equal(t, "2001020304", fmt.Sprintf("%x", ToBytes(ToPrefixed(FromBytes([]byte{1, 2, 3, 4}), true))))
+    //
+    //   require.Equal(t, "636f696e", fmt.Sprintf("%x", []byte("coin")))
+    // }
+    #[test]
+    fn test_leaf_hash() {
+        assert_eq!(hex::encode([1, 2, 3, 4]), "01020304");
+        assert_eq!(hex::encode(b"verb"), "76657262");
+
+        // "buffer to nibbles
+        assert_eq!(
+            encode_nibbles(&Nibble::from_bytes([1, 2, 3, 4].to_vec())),
+            "0001000200030004"
+        );
+
+        // ToPrefixed
+        assert_eq!(
+            encode_nibbles(&Nibble::to_prefixed(Nibble::from_bytes([1, 2, 3, 4].to_vec()), true)),
+            "02000001000200030004"
+        );
+
+        // ToBuffer
+        assert_eq!(
+            hex::encode(Nibble::to_bytes(Nibble::to_prefixed(Nibble::from_bytes([1, 2, 3, 4].to_vec()), true))),
+            "2001020304"
+        );
+
+        assert_eq!(hex::encode(b"coin"), "636f696e");
+    }
+
+    // func Test3Nibbles(t *testing.T) {
+    //   key, value := []byte{5, 0, 6}, []byte("coin")
+    //   hexs := printEachCalculationSteps(key, value, true)
+    //   fmt.Printf("key_hex: %x\n", key)
+    //   fmt.Printf("value_hex: %x\n", value)
+    //   fmt.Printf("key in nibbles: %s\n", hexs["key in nibbles"])
+    //   fmt.Printf("key in nibbles, and prefixed: %s\n", hexs["key in nibbles, and prefixed"])
+    //   fmt.Printf("key in nibbles, and prefixed, and convert back to buffer: %s\n",
+    //     hexs["key in nibbles, and prefixed, and convert back to buffer"])
+    //   fmt.Printf("beforeRLP: %s\n", hexs["beforeRLP"])
+    //   fmt.Printf("afterRLP: %s\n", hexs["afterRLP"])
+    //   fmt.Printf("hash: %s\n", hexs["hash"])
+    //   require.Equal(t, "c5442690f038fcc0b8b8949b4f5149db8c0bee917be6355dc2db1855e9675700",
+    //     hexs["hash"])
+    // }
+    #[test]
+    fn test_3_nibbles() {
+        let key = &[5, 0, 6];
+        let value = b"coin";
+        let hexs = print_each_calculation_steps(key, value, true);
+        println!("key_hex: {:?}", key);
+        println!("value_hex: {:?}", value);
+        println!("key in nibbles: {:?}", hexs[0].1);
+        println!("key in nibbles, and prefixed: {:?}", hexs[1].1);
+        println!("key in nibbles, and prefixed, and convert back to buffer: {:?}", hexs[2].1);
+        println!("beforeRLP: {:?}", hexs[3].1);
+        println!("afterRLP: {:?}", hexs[4].1);
+        println!("hash: {:?}", hexs[5].1);
+        assert_eq!(
+            hexs[5].1,
+            "c5442690f038fcc0b8b8949b4f5149db8c0bee917be6355dc2db1855e9675700"
+        );
+    }
+
+    // func TestLeafNode(t *testing.T) {
+    //   nibbles, value := []byte{1, 2, 3, 4}, []byte("verb")
+    //   l := NewLeafNodeFromBytes(nibbles, value)
+    //   require.Equal(t, "2bafd1eef58e8707569b7c70eb2f91683136910606ba7e31d07572b8b67bf5c6", fmt.Sprintf("%x", l.Hash()))
+    // }
+    #[test]
+    fn test_leaf_node() {
+        let nibbles = &[1, 2, 3, 4];
+        let value = b"verb";
+        let l = LeafNode::new_from_bytes(nibbles, value);
+        assert_eq!(
+            hex::encode(l.hash()),
+            "2bafd1eef58e8707569b7c70eb2f91683136910606ba7e31d07572b8b67bf5c6"
+        );
+    }
+
+    // func TestLeafNode2(t *testing.T) {
+    //   // t.Skip()
+    //   nibbles, value := []byte{5, 0, 6}, []byte("coin")
+    //   l, err := NewLeafNodeFromNibbleBytes(nibbles, value)
+    //   require.NoError(t, err)
+    //   require.Equal(t, "c37ec985b7a88c2c62beb268750efe657c36a585beb435eb9f43b839846682ce", fmt.Sprintf("%x", l.Hash()))
+    // }
+    #[test]
+    fn test_leaf_node_2() {
+        let nibbles = &[5, 0, 6];
+        let value = b"coin";
+        let l = LeafNode::new_from_nibble_bytes(nibbles, value).unwrap();
+        assert_eq!(
+            hex::encode(l.hash()),
+            "c37ec985b7a88c2c62beb268750efe657c36a585beb435eb9f43b839846682ce"
+        );
+    }
+}
@@ -0,0 +1,46 @@
+extern crate hex;
+extern crate tiny_keccak;
+
+pub mod branch;
+pub mod crypto;
+pub mod db;
+pub mod empty;
+pub mod extension;
+pub mod hashing;
+pub mod header;
+pub mod hexutil;
+pub mod leaf;
+pub mod nibbles;
+pub mod nodes;
+pub mod proof;
+pub mod receipt;
+pub mod securetrie;
+pub mod slot;
+pub mod stacktrie;
+pub mod storage_proof;
+pub mod storage_value;
+pub mod transaction;
+pub mod trie;
+pub mod withdrawal;
+
+pub use branch::BranchNode;
+pub use db::{FileDb, MemoryDb, NodeDb};
+pub use empty::{is_empty_node, EMPTY_NODE_HASH};
+pub use extension::ExtensionNode;
+pub use hashing::{derive_root, derive_sha, DerivableList, TrieHasher};
+pub use header::Header;
+pub use leaf::LeafNode;
+pub use nibbles::Nibble;
+pub use nodes::{ChildRef, Node};
+pub use proof::{
+    verify_many, verify_membership, verify_proof, verify_range_proof, Absence, Membership, Proof, ProofDb, ProofError,
+};
+pub use receipt::{create_bloom, Bloom, Log, Receipt, ReceiptError, VerifiedReceipt};
+pub use securetrie::SecureTrie;
+pub use slot::{locate, Layout, Location, SolType, Step};
+pub use stacktrie::StackTrie;
+pub use storage_proof::{AccountState, EthGetProofResponse, GetProofError, StorageProof, StorageStateResult};
+pub use storage_value::{decode_storage_value, decode_value, decode_word, SolValue, ValueError};
+pub use transaction::{AccessList, AccessTuple, Authorization, Transaction, TxType};
+pub use trie::{Entry, Trie, TrieError};
+pub use withdrawal::Withdrawal;
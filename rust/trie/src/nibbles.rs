@@ -0,0 +1,327 @@
+// type Nibble byte
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub struct Nibble(pub u8);
+
+impl Nibble {
+    pub fn to_usize(&self) -> usize {
+        self.0 as usize
+    }
+
+    // func IsNibble(nibble byte) bool {
+    //   n := int(nibble)
+    //   // 0-9 && a-f
+    //   return n >= 0 && n < 16
+    // }
+    pub fn is_nibble(nibble: u8) -> bool {
+        // 0-9 && a-f
+        nibble < 16
+    }
+
+    // func FromNibbleByte(n byte) (Nibble, error) {
+    //   if !IsNibble(n) {
+    //     return 0, fmt.Errorf("non-nibble byte: %v", n)
+    //   }
+    //   return Nibble(n), nil
+    // }
+    pub fn from_nibble_byte(n: u8) -> Result<Nibble, &'static str> {
+        if !Nibble::is_nibble(n) {
+            return Err("Non-nibble byte");
+        }
+        Ok(Nibble(n))
+    }
+
+    // // nibbles contain one nibble per byte
+    // func FromNibbleBytes(nibbles []byte) ([]Nibble, error) {
+    //   ns := make([]Nibble, 0, len(nibbles))
+    //   for _, n := range nibbles {
+    //     nibble, err := FromNibbleByte(n)
+    //     if err != nil {
+    //       return nil, fmt.Errorf("contains non-nibble byte: %w", err)
+    //     }
+    //     ns = append(ns, nibble)
+    //   }
+    //   return ns, nil
+    // }
+    pub fn from_nibble_bytes(nibbles: Vec<u8>) -> Result<Vec<Nibble>, &'static str> {
+        let mut ns = Vec::with_capacity(nibbles.len());
+        for n in nibbles {
+            let nibble = Nibble::from_nibble_byte(n)?;
+            ns.push(nibble);
+        }
+        Ok(ns)
+    }
+
+    // func FromByte(b byte) []Nibble {
+    //   return []Nibble{
+    //     Nibble(byte(b >> 4)),
+    //     Nibble(byte(b % 16)),
+    //   }
+    // }
+    pub fn from_byte(b: u8) -> Vec<Nibble> {
+        vec![
+            Nibble(b >> 4),
+            Nibble(b % 16),
+        ]
+    }
+
+    // func FromBytes(bs []byte) []Nibble {
+    //   ns := make([]Nibble, 0, len(bs)*2)
+    //   for _, b := range bs {
+    //     ns = append(ns, FromByte(b)...)
+    //   }
+    //   return ns
+    // }
+    pub fn from_bytes(bs: Vec<u8>) -> Vec<Nibble> {
+        let mut ns = Vec::with_capacity(bs.len() * 2);
+        for b in bs {
+            ns.extend(Nibble::from_byte(b));
+        }
+        ns
+    }
+
+    // func FromString(s string) []Nibble {
+    //   return FromBytes([]byte(s))
+    // }
+    pub fn from_string(s: String) -> Vec<Nibble> {
+        Nibble::from_bytes(s.into_bytes())
+    }
+
+    // // ToPrefixed add nibble prefix to a slice of nibbles to make its length even
+    // // the prefix indicts whether a node is a leaf node.
+    // func ToPrefixed(ns []Nibble, isLeafNode bool) []Nibble {
+    //   // create prefix
+    //   var prefixBytes []Nibble
+    //   // odd number of nibbles
+    //   if len(ns)%2 > 0 {
+    //     prefixBytes = []Nibble{1}
+    //   } else {
+    //     // even number of nibbles
+    //     prefixBytes = []Nibble{0, 0}
+    //   }
+    //
+    //   // append prefix to all nibble bytes
+    //   prefixed := make([]Nibble, 0, len(prefixBytes)+len(ns))
+    //   prefixed = append(prefixed, prefixBytes...)
+    //   prefixed = append(prefixed, ns...)
+    //
+    //   // update prefix if is leaf node
+    //   if isLeafNode {
+    //     prefixed[0] += 2
+    //   }
+    //
+    //   return prefixed
+    // }
+    pub fn to_prefixed(ns: Vec<Nibble>, is_leaf_node: bool) -> Vec<Nibble> {
+        let mut prefix_bytes = if !ns.len().is_multiple_of(2) {
+            vec![Nibble(1)]
+        } else {
+            vec![Nibble(0), Nibble(0)]
+        };
+
+        let mut prefixed = Vec::with_capacity(prefix_bytes.len() + ns.len());
+        prefixed.append(&mut prefix_bytes);
+        prefixed.extend(ns);
+
+        if is_leaf_node {
+            prefixed[0].0 += 2;
+        }
+
+        prefixed
+    }
+
+    // FromPrefixed is the inverse of ToPrefixed applied to a compact encoded
+    // path: it drops the flag nibble (0-3) and the padding nibble of even paths,
+    // and reports whether the path belongs to a leaf node.
+    pub fn from_prefixed(bs: &[u8]) -> Result<(Vec<Nibble>, bool), &'static str> {
+        let ns = Nibble::from_bytes(bs.to_vec());
+        let Some(flag) = ns.first() else {
+            return Err("Empty prefixed path");
+        };
+
+        let (is_leaf_node, is_odd) = match flag.0 {
+            0 => (false, false),
+            1 => (false, true),
+            2 => (true, false),
+            3 => (true, true),
+            _ => return Err("Invalid prefix flag"),
+        };
+
+        if is_odd {
+            return Ok((ns[1..].to_vec(), is_leaf_node));
+        }
+        if ns[1] != Nibble(0) {
+            return Err("Invalid prefix padding");
+        }
+        Ok((ns[2..].to_vec(), is_leaf_node))
+    }
+
+    // // ToBytes converts a slice of nibbles to a byte slice
+    // // assuming the nibble slice has even number of nibbles.
+    // func ToBytes(ns []Nibble) []byte {
+    //   buf := make([]byte, 0, len(ns)/2)
+    //
+    //   for i := 0; i < len(ns); i += 2 {
+    //     b := byte(ns[i]<<4) + byte(ns[i+1])
+    //     buf = append(buf, b)
+    //   }
+    //
+    //   return buf
+    // }
+    pub fn to_bytes(ns: Vec<Nibble>) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(ns.len() / 2);
+
+        for ns_chunk in ns.chunks_exact(2) {
+            let b = (ns_chunk[0].0 << 4) + ns_chunk[1].0;
+            buf.push(b);
+        }
+
+        buf
+    }
+
+    // // [0,1,2,3], [0,1,2] => 3
+    // // [0,1,2,3], [0,1,2,3] => 4
+    // // [0,1,2,3], [0,1,2,3,4] => 4
+    // func PrefixMatchedLen(node1 []Nibble, node2 []Nibble) int {
+    //   matched := 0
+    //   for i := 0; i < len(node1) && i < len(node2); i++ {
+    //     n1, n2 := node1[i], node2[i]
+    //     if n1 == n2 {
+    //       matched++
+    //     } else {
+    //       break
+    //     }
+    //   }
+    //
+    //   return matched
+    // }
+    pub fn prefix_matched_len(node1: &[Nibble], node2: &[Nibble]) -> usize {
+        node1.iter().zip(node2.iter()).take_while(|&(n1, n2)| n1 == n2).count()
+    }
+}
+
+pub fn encode_nibbles(nibbles: &Vec<Nibble>) -> String {
+    let mut result = String::with_capacity(nibbles.len() * 2);
+    for nibble in nibbles {
+        let byte = nibble.0;
+        let hex_chars = hex::encode_upper([byte]);
+        result.push_str(&hex_chars);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // func TestIsNibble(t *testing.T) {
+    //   for i := 0; i < 20; i++ {
+    //     isNibble := i >= 0 && i < 16
+    //     require.Equal(t, isNibble, IsNibble(byte(i)), i)
+    //   }
+    // }
+    #[test]
+    fn test_is_nibble() {
+        for i in 0..20 {
+            let is_nibble = i < 16;
+            assert_eq!(is_nibble, Nibble::is_nibble(i as u8), "{}", i);
+        }
+    }
+
+    // func TestToPrefixed(t *testing.T) {
+    //   cases := []struct {
+    //     ns         []Nibble
+    //     isLeafNode bool
+    //     expected   []Nibble
+    //   }{
+    //     {[]Nibble{1}, false, []Nibble{1, 1},},
+    //     {[]Nibble{1, 2}, false, []Nibble{0, 0, 1, 2},},
+    //     {[]Nibble{1}, true, []Nibble{3, 1},},
+    //     {[]Nibble{1, 2}, true, []Nibble{2, 0, 1, 2},},
+    //     {[]Nibble{5, 0, 6}, true, []Nibble{3, 5, 0, 6},},
+    //     {[]Nibble{14, 3}, false, []Nibble{0, 0, 14, 3},},
+    //     {[]Nibble{9, 3, 6, 5}, true, []Nibble{2, 0, 9, 3, 6, 5},},
+    //     {[]Nibble{1, 3, 3, 5}, true, []Nibble{2, 0, 1, 3, 3, 5},},
+    //     {[]Nibble{7}, true, []Nibble{3, 7},},
+    //   }
+    //
+    //   for _, c := range cases {
+    //     require.Equal(t,
+    //       c.expected,
+    //       ToPrefixed(c.ns, c.isLeafNode))
+    //   }
+    // }
+    #[test]
+    fn test_to_prefixed() {
+        let cases = vec![
+            (vec![Nibble(1)], false, vec![Nibble(1), Nibble(1)]),
+            (vec![Nibble(1), Nibble(2)], false, vec![Nibble(0), Nibble(0), Nibble(1), Nibble(2)]),
+            (vec![Nibble(1)], true, vec![Nibble(3), Nibble(1)]),
+            (vec![Nibble(1), Nibble(2)], true, vec![Nibble(2), Nibble(0), Nibble(1), Nibble(2)]),
+            (vec![Nibble(5), Nibble(0), Nibble(6)], true, vec![Nibble(3), Nibble(5), Nibble(0), Nibble(6)]),
+            (vec![Nibble(14), Nibble(3)], false, vec![Nibble(0), Nibble(0), Nibble(14), Nibble(3)]),
+            (vec![Nibble(9), Nibble(3), Nibble(6), Nibble(5)], true, vec![Nibble(2), Nibble(0), Nibble(9), Nibble(3), Nibble(6), Nibble(5)]),
+            (vec![Nibble(1), Nibble(3), Nibble(3), Nibble(5)], true, vec![Nibble(2), Nibble(0), Nibble(1), Nibble(3), Nibble(3), Nibble(5)]),
+            (vec![Nibble(7)], true, vec![Nibble(3), Nibble(7)]),
+        ];
+
+        for (ns, is_leaf_node, expected) in cases {
+            assert_eq!(expected, Nibble::to_prefixed(ns, is_leaf_node));
+        }
+    }
+
+    #[test]
+    fn test_from_prefixed() {
+        let cases = vec![
+            (vec![], false),
+            (vec![], true),
+            (vec![Nibble(1)], false),
+            (vec![Nibble(1), Nibble(2)], true),
+            (vec![Nibble(5), Nibble(0), Nibble(6)], true),
+            (vec![Nibble(14), Nibble(3)], false),
+        ];
+
+        for (ns, is_leaf_node) in cases {
+            let prefixed = Nibble::to_bytes(Nibble::to_prefixed(ns.clone(), is_leaf_node));
+            assert_eq!(Ok((ns, is_leaf_node)), Nibble::from_prefixed(&prefixed));
+        }
+
+        assert!(Nibble::from_prefixed(&[]).is_err());
+        assert!(Nibble::from_prefixed(&[0x40]).is_err());
+        assert!(Nibble::from_prefixed(&[0x21]).is_err());
+    }
+
+    // func TestFromBytes(t *testing.T) {
+    //   // [1, 100] -> ['0x01', '0x64']
+    //   require.Equal(t, []Nibble{0, 1, 6, 4}, FromBytes([]byte{1, 100}))
+    // }
+    #[test]
+    fn test_from_bytes() {
+        // [1, 100] -> ['0x01', '0x64']
+        assert_eq!(
+            vec![Nibble(0), Nibble(1), Nibble(6), Nibble(4)],
+            Nibble::from_bytes([1, 100].to_vec())
+        );
+    }
+
+    // func TestToBytes(t *testing.T) {
+    //   bytes := []byte{0, 1, 2, 3}
+    //   require.Equal(t, bytes, ToBytes(FromBytes(bytes)))
+    // }
+    #[test]
+    fn test_to_bytes() {
+        let bytes = &[0, 1, 2, 3];
+        assert_eq!(bytes.to_vec(), Nibble::to_bytes(Nibble::from_bytes(bytes.to_vec())));
+    }
+
+    // func TestPrefixMatchedLen(t *testing.T) {
+    //   require.Equal(t, 3, PrefixMatchedLen([]Nibble{0, 1, 2, 3}, []Nibble{0, 1, 2}))
+    //   require.Equal(t, 4, PrefixMatchedLen([]Nibble{0, 1, 2, 3}, []Nibble{0, 1, 2, 3}))
+    //   require.Equal(t, 4, PrefixMatchedLen([]Nibble{0, 1, 2, 3}, []Nibble{0, 1, 2, 3, 4}))
+    // }
+    #[test]
+    fn test_prefix_matched_len() {
+        assert_eq!(3, Nibble::prefix_matched_len(&[Nibble(0), Nibble(1), Nibble(2), Nibble(3)], &[Nibble(0), Nibble(1), Nibble(2)]));
+        assert_eq!(4, Nibble::prefix_matched_len(&[Nibble(0), Nibble(1), Nibble(2), Nibble(3)], &[Nibble(0), Nibble(1), Nibble(2), Nibble(3)]));
+        assert_eq!(4, Nibble::prefix_matched_len(&[Nibble(0), Nibble(1), Nibble(2), Nibble(3)], &[Nibble(0), Nibble(1), Nibble(2), Nibble(3), Nibble(4)]));
+    }
+}
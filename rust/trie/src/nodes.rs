@@ -0,0 +1,359 @@
+use std::fmt;
+use std::sync::OnceLock;
+
+use rlp::{DecoderError, Rlp, RlpStream};
+
+use crate::branch::BranchNode;
+use crate::crypto::keccak256;
+use crate::empty::EMPTY_NODE_HASH;
+use crate::extension::ExtensionNode;
+use crate::leaf::LeafNode;
+use crate::nibbles::Nibble;
+
+// type Node interface {
+//   Hash() []byte // common.Hash
+//   Raw() []interface{}
+// }
+//
+// The Go interface is open and `nil` stands for the empty node. Here the set of
+// node kinds is closed, so `Trie::get`/`Trie::put` can match on it directly.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum Node {
+    #[default]
+    Empty,
+    Leaf(LeafNode),
+    Extension(ExtensionNode),
+    Branch(Box<BranchNode>),
+    // a subtrie only known by its hash, like a hashed child of a decoded node
+    Hash([u8; 32]),
+}
+
+// How a parent node refers to one of its children in its own encoding.
+//
+// if len(Serialize(node)) >= 32 {
+//   hashes[i] = node.Hash()
+// } else {
+//   hashes[i] = node.Raw()
+// }
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChildRef {
+    // the child serializes to 32 bytes or more, it is referenced by its hash
+    Hash([u8; 32]),
+    // the child serializes to less than 32 bytes, its encoding is embedded as-is
+    Embedded(Vec<u8>),
+}
+
+impl ChildRef {
+    // appends the reference as one item of the parent's list
+    pub fn append_to(&self, s: &mut RlpStream) {
+        match self {
+            ChildRef::Hash(hash) => {
+                s.append(&hash.as_slice());
+            }
+            ChildRef::Embedded(encoded) => {
+                s.append_raw(encoded, 1);
+            }
+        }
+    }
+}
+
+// The encoding and hash of a leaf, extension or branch node, computed on first
+// use. Every put or delete clears the cache of the nodes on the path of the key,
+// so that hashing the trie again only encodes those. The fields of the nodes
+// are only changed by the crate, outside of it they are read with getters and
+// written with setters that clear the cache.
+#[derive(Clone, Default)]
+pub struct NodeCache {
+    encoded: OnceLock<Vec<u8>>,
+    hash: OnceLock<[u8; 32]>,
+}
+
+impl NodeCache {
+    // with_encoded is the cache of a node decoded from encoded.
+    pub(crate) fn with_encoded(encoded: Vec<u8>) -> NodeCache {
+        NodeCache { encoded: OnceLock::from(encoded), hash: OnceLock::new() }
+    }
+
+    pub(crate) fn encoded(&self, encode: impl FnOnce() -> Vec<u8>) -> &[u8] {
+        self.encoded.get_or_init(encode)
+    }
+
+    pub(crate) fn hash(&self, encode: impl FnOnce() -> Vec<u8>) -> [u8; 32] {
+        *self.hash.get_or_init(|| keccak256(self.encoded(encode)))
+    }
+
+    pub fn clear(&mut self) {
+        self.encoded.take();
+        self.hash.take();
+    }
+}
+
+// the cache does not change what a node is
+impl PartialEq for NodeCache {
+    fn eq(&self, _: &NodeCache) -> bool {
+        true
+    }
+}
+
+impl Eq for NodeCache {}
+
+impl fmt::Debug for NodeCache {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("NodeCache")
+    }
+}
+
+impl Node {
+    pub fn is_empty(&self) -> bool {
+        matches!(self, Node::Empty)
+    }
+
+    pub fn hash(&self) -> Vec<u8> {
+        hash(self)
+    }
+
+    pub fn serialize(&self) -> Vec<u8> {
+        serialize(self)
+    }
+
+    // Raw() []interface{}
+    //
+    // Go builds a nested []interface{} and hands it to rlp.EncodeToBytes, here
+    // every node appends its item straight into the parent's RlpStream.
+    pub fn raw(&self, s: &mut RlpStream) {
+        match self {
+            Node::Empty => {
+                s.append_empty_data();
+            }
+            Node::Leaf(leaf) => leaf.raw(s),
+            Node::Extension(ext) => ext.raw(s),
+            Node::Branch(branch) => branch.raw(s),
+            Node::Hash(hash) => {
+                s.append(&hash.as_slice());
+            }
+        }
+    }
+
+    // clear_cache is called on a node whose encoding is about to change.
+    pub fn clear_cache(&mut self) {
+        match self {
+            Node::Leaf(leaf) => leaf.cache.clear(),
+            Node::Extension(ext) => ext.cache.clear(),
+            Node::Branch(branch) => branch.cache.clear(),
+            Node::Empty | Node::Hash(_) => {}
+        }
+    }
+
+    pub fn child_ref(&self) -> ChildRef {
+        let encoded = match self {
+            Node::Hash(hash) => return ChildRef::Hash(*hash),
+            Node::Empty => return ChildRef::Embedded(serialize(self)),
+            Node::Leaf(leaf) => leaf.encoded(),
+            Node::Extension(ext) => ext.encoded(),
+            Node::Branch(branch) => branch.encoded(),
+        };
+        if encoded.len() >= 32 {
+            ChildRef::Hash(self.hash().try_into().unwrap())
+        } else {
+            // if node can be serialized to less than 32 bits, then
+            // use Serialized directly.
+            // it has to be ">=", rather than ">",
+            // so that when deserialized, the content can be distinguished
+            // by length
+            ChildRef::Embedded(encoded.to_vec())
+        }
+    }
+
+    // Decode parses the RLP encoding of a node, the inverse of Serialize.
+    // Embedded children are decoded in place, children referenced by their
+    // hash become Node::Hash.
+    pub fn decode(encoded: &[u8]) -> Result<Node, DecoderError> {
+        decode_node(&Rlp::new(encoded))
+    }
+}
+
+// decode_node reads a 2-item leaf or extension node or a 17-item branch node,
+// or the empty string of the empty node.
+fn decode_node(item: &Rlp) -> Result<Node, DecoderError> {
+    if !item.is_list() {
+        if item.data()?.is_empty() {
+            return Ok(Node::Empty);
+        }
+        return Err(DecoderError::Custom("node is neither a list nor empty"));
+    }
+
+    match item.item_count()? {
+        // leaf or extension node
+        2 => {
+            let (path, is_leaf_node) = Nibble::from_prefixed(item.at(0)?.data()?).map_err(DecoderError::Custom)?;
+            if is_leaf_node {
+                let mut leaf = LeafNode::new_leaf_node_from_nibbles(path, item.val_at(1)?);
+                leaf.cache = NodeCache::with_encoded(item.as_raw().to_vec());
+                return Ok(Node::Leaf(leaf));
+            }
+
+            let next = decode_child(&item.at(1)?)?;
+            if next.is_empty() {
+                return Err(DecoderError::Custom("extension node without child"));
+            }
+            let mut ext = ExtensionNode::new(path, next);
+            ext.cache = NodeCache::with_encoded(item.as_raw().to_vec());
+            Ok(Node::Extension(ext))
+        }
+
+        // branch node
+        17 => {
+            let mut branch = BranchNode::new();
+            for i in 0..16 {
+                branch.set_branch(Nibble(i as u8), decode_child(&item.at(i)?)?);
+            }
+            let value: Vec<u8> = item.val_at(16)?;
+            if !value.is_empty() {
+                branch.set_value(value);
+            }
+            branch.cache = NodeCache::with_encoded(item.as_raw().to_vec());
+            Ok(Node::Branch(Box::new(branch)))
+        }
+
+        _ => Err(DecoderError::Custom("unexpected number of items")),
+    }
+}
+
+// decode_child reads a child slot of an extension or branch node: an empty
+// string is an empty slot, a 32-byte string a hash, and a list an embedded node.
+fn decode_child(item: &Rlp) -> Result<Node, DecoderError> {
+    if item.is_list() {
+        return decode_node(item);
+    }
+
+    let data = item.data()?;
+    match data.len() {
+        0 => Ok(Node::Empty),
+        32 => Ok(Node::Hash(data.try_into().unwrap())),
+        _ => Err(DecoderError::Custom("child reference is neither a hash nor an embedded node")),
+    }
+}
+
+// func Hash(node Node) []byte {
+//   if IsEmptyNode(node) {
+//     return EmptyNodeHash
+//   }
+//   return node.Hash()
+// }
+pub fn hash(node: &Node) -> Vec<u8> {
+    match node {
+        Node::Empty => EMPTY_NODE_HASH.to_vec(),
+        Node::Leaf(leaf) => leaf.hash(),
+        Node::Extension(ext) => ext.hash(),
+        Node::Branch(branch) => branch.hash(),
+        Node::Hash(hash) => hash.to_vec(),
+    }
+}
+
+// func Serialize(node Node) []byte {
+//   var raw interface{}
+//
+//   if IsEmptyNode(node) {
+//     raw = EmptyNodeRaw
+//   } else {
+//     raw = node.Raw()
+//   }
+//
+//   rlp, err := rlp.EncodeToBytes(raw)
+//   if err != nil {
+//     panic(err)
+//   }
+//
+//   return rlp
+// }
+pub fn serialize(node: &Node) -> Vec<u8> {
+    match node {
+        Node::Leaf(leaf) => return leaf.serialize(),
+        Node::Extension(ext) => return ext.serialize(),
+        Node::Branch(branch) => return branch.serialize(),
+        Node::Empty | Node::Hash(_) => {}
+    }
+    let mut s = RlpStream::new();
+    node.raw(&mut s);
+    s.out().to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trie::Trie;
+
+    fn hex_nodes(nodes: &serde_json::Value) -> Vec<Vec<u8>> {
+        nodes
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|node| hex::decode(node.as_str().unwrap().trim_start_matches("0x")).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_decode_round_trip() {
+        let mut tr = Trie::new();
+        for i in 0..50u8 {
+            tr.put(&[i / 7, i], &vec![i; 1 + i as usize % 40]);
+        }
+        tr.put(&[3], b"branch value");
+
+        for i in 0..50u8 {
+            for encoded in tr.prove(&[i / 7, i]).unwrap().serialize() {
+                assert_eq!(encoded, Node::decode(&encoded).unwrap().serialize());
+            }
+        }
+    }
+
+    #[test]
+    fn test_decode_nodes() {
+        let leaf = LeafNode::new_from_nibble_bytes(&[5, 0, 6], b"coin").unwrap();
+        assert_eq!(Ok(Node::Leaf(leaf.clone())), Node::decode(&leaf.serialize()));
+
+        // the leaf serializes to less than 32 bytes, so it is embedded
+        let mut branch = BranchNode::new();
+        branch.set_branch(Nibble(0), Node::Leaf(leaf));
+        branch.set_value(b"verb".to_vec());
+        let ext = Node::Extension(ExtensionNode::new(vec![Nibble(1), Nibble(2), Nibble(3), Nibble(4)], Node::Branch(Box::new(branch))));
+        assert_eq!(Ok(ext.clone()), Node::decode(&ext.serialize()));
+
+        // a longer leaf is referenced by hash
+        let leaf = LeafNode::new_from_nibble_bytes(&[5, 0, 6], &[7; 40]).unwrap();
+        let ext = ExtensionNode::new(vec![Nibble(1)], Node::Leaf(leaf.clone()));
+        let hash: [u8; 32] = leaf.hash().try_into().unwrap();
+        assert_eq!(Ok(Node::Extension(ExtensionNode::new(vec![Nibble(1)], Node::Hash(hash)))), Node::decode(&ext.serialize()));
+
+        assert_eq!(Ok(Node::Empty), Node::decode(&[0x80]));
+    }
+
+    #[test]
+    fn test_decode_invalid() {
+        // a list of 3 items
+        assert_eq!(Err(DecoderError::Custom("unexpected number of items")), Node::decode(&[0xc3, 0x01, 0x02, 0x03]));
+        // flag nibble 4
+        assert_eq!(Err(DecoderError::Custom("Invalid prefix flag")), Node::decode(&[0xc2, 0x40, 0x01]));
+        // extension node pointing to a 2-byte string
+        assert!(Node::decode(&[0xc4, 0x11, 0x82, 0x01, 0x02]).is_err());
+        // not RLP at all
+        assert!(Node::decode(&[0xf9, 0x02]).is_err());
+    }
+
+    #[test]
+    fn test_decode_eip1186_proof() {
+        let json: serde_json::Value = serde_json::from_str(include_str!("../../../eip1186_proof.json")).unwrap();
+        let nodes = hex_nodes(&json["result"]["accountProof"]);
+
+        for encoded in &nodes {
+            assert_eq!(encoded, &Node::decode(encoded).unwrap().serialize());
+        }
+
+        // every node is a hashed child of the branch node before it
+        for pair in nodes.windows(2) {
+            let Node::Branch(branch) = Node::decode(&pair[0]).unwrap() else { panic!("expected a branch node") };
+            assert!(branch.branches.contains(&Node::Hash(keccak256(&pair[1]))));
+        }
+        assert!(matches!(Node::decode(nodes.last().unwrap()), Ok(Node::Leaf(_))));
+    }
+}
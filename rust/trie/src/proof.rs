@@ -0,0 +1,1112 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use rlp::DecoderError;
+
+use crate::crypto::keccak256;
+use crate::empty::EMPTY_NODE_HASH;
+use crate::extension::ExtensionNode;
+use crate::nibbles::Nibble;
+use crate::nodes::Node;
+use crate::trie::{Trie, TrieError};
+
+// type Proof interface {
+//   // Put inserts the given value into the key-value data store.
+//   Put(key []byte, value []byte) error
+//
+//   // Delete removes the key from the key-value data store.
+//   Delete(key []byte) error
+//
+//   // Has retrieves if a key is present in the key-value data store.
+//   Has(key []byte) (bool, error)
+//
+//   // Get retrieves the given key if it's present in the key-value data store.
+//   Get(key []byte) ([]byte, error)
+//
+//   // Serialize returns the serialized proof
+//   Serialize() [][]byte
+// }
+//
+// The Go ProofDB is a map keyed by node hash, which loses the order of the
+// nodes. Proof keeps the RLP-encoded nodes in root-to-leaf order instead and
+// still answers lookups by hash.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Proof {
+    nodes: Vec<Vec<u8>>,
+}
+
+impl Proof {
+    // func NewProofDB() *ProofDB {
+    //   return &ProofDB{
+    //     kv: make(map[string][]byte),
+    //   }
+    // }
+    pub fn new() -> Proof {
+        Proof { nodes: Vec::new() }
+    }
+
+    pub fn from_nodes(nodes: Vec<Vec<u8>>) -> Proof {
+        Proof { nodes }
+    }
+
+    // func (w *ProofDB) Put(key []byte, value []byte) error {
+    //   keyS := fmt.Sprintf("%x", key)
+    //   w.kv[keyS] = value
+    //   return nil
+    // }
+    pub fn put(&mut self, node: Vec<u8>) {
+        self.nodes.push(node);
+    }
+
+    // func (w *ProofDB) Has(key []byte) (bool, error) {
+    //   keyS := fmt.Sprintf("%x", key)
+    //   _, ok := w.kv[keyS]
+    //   return ok, nil
+    // }
+    pub fn has(&self, hash: &[u8]) -> bool {
+        self.get(hash).is_some()
+    }
+
+    // func (w *ProofDB) Get(key []byte) ([]byte, error) {
+    //   keyS := fmt.Sprintf("%x", key)
+    //   val, ok := w.kv[keyS]
+    //   if !ok {
+    //     return nil, fmt.Errorf("not found")
+    //   }
+    //   return val, nil
+    // }
+    pub fn get(&self, hash: &[u8]) -> Option<&[u8]> {
+        self.nodes
+            .iter()
+            .find(|node| keccak256(node) == hash)
+            .map(|node| node.as_slice())
+    }
+
+    // func (w *ProofDB) Serialize() [][]byte {
+    //   nodes := make([][]byte, 0, len(w.kv))
+    //   for _, value := range w.kv {
+    //     nodes = append(nodes, value)
+    //   }
+    //   return nodes
+    // }
+    pub fn serialize(&self) -> Vec<Vec<u8>> {
+        self.nodes.clone()
+    }
+
+    pub fn nodes(&self) -> &[Vec<u8>] {
+        &self.nodes
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+}
+
+// type ProofDB struct {
+//   kv map[string][]byte
+// }
+//
+// ProofDb is the set of proof nodes for several keys, keyed by node hash, so
+// that the nodes shared by the paths of the keys are only stored once.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProofDb {
+    kv: HashMap<[u8; 32], Vec<u8>>,
+}
+
+impl ProofDb {
+    pub fn new() -> ProofDb {
+        ProofDb { kv: HashMap::new() }
+    }
+
+    pub fn from_nodes(nodes: Vec<Vec<u8>>) -> ProofDb {
+        let mut db = ProofDb::new();
+        for node in nodes {
+            db.put(node);
+        }
+        db
+    }
+
+    // put stores the node under its hash
+    pub fn put(&mut self, node: Vec<u8>) {
+        self.kv.insert(keccak256(&node), node);
+    }
+
+    pub fn delete(&mut self, hash: &[u8; 32]) {
+        self.kv.remove(hash);
+    }
+
+    pub fn has(&self, hash: &[u8; 32]) -> bool {
+        self.kv.contains_key(hash)
+    }
+
+    pub fn get(&self, hash: &[u8; 32]) -> Option<&[u8]> {
+        self.kv.get(hash).map(|node| node.as_slice())
+    }
+
+    // serialize returns the nodes ordered by hash, so that the same set of
+    // nodes always serializes the same way
+    pub fn serialize(&self) -> Vec<Vec<u8>> {
+        let mut entries: Vec<_> = self.kv.iter().collect();
+        entries.sort_by_key(|(hash, _)| *hash);
+        entries.into_iter().map(|(_, node)| node.clone()).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.kv.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.kv.is_empty()
+    }
+}
+
+impl From<Proof> for ProofDb {
+    fn from(proof: Proof) -> Self {
+        ProofDb::from_nodes(proof.nodes)
+    }
+}
+
+impl Trie {
+    // FromProof builds a partial trie from the proof nodes under root. The
+    // subtries missing from the proof are kept as Node::Hash, reading or
+    // updating a key that leads into one of them fails with MissingNode.
+    pub fn from_proof(root: [u8; 32], proof: &ProofDb) -> Result<Trie, ProofError> {
+        if root == EMPTY_NODE_HASH {
+            return Ok(Trie::new());
+        }
+        if !proof.has(&root) {
+            return Err(ProofError::MissingNode(root));
+        }
+        let mut tr = Trie::new();
+        tr.root = load(Node::Hash(root), proof)?;
+        Ok(tr)
+    }
+
+    // Prove returns the merkle proof for the given key, which is the list of
+    // RLP-encoded nodes on the path from the root to the value, in that order.
+    // Nodes whose encoding is shorter than 32 bytes are embedded in their
+    // parent and are not repeated, the root node is always included.
+    //
+    // Prove panics when a node on the path of the key cannot be loaded, like
+    // Get, partial tries and tries backed by a database are proven with TryProve.
+    pub fn prove(&self, key: &[u8]) -> Option<Proof> {
+        self.try_prove(key).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_prove(&self, key: &[u8]) -> Result<Option<Proof>, TrieError> {
+        let (proof, found) = self.prove_path(key)?;
+        Ok(found.then_some(proof))
+    }
+
+    // ProveAbsence returns the proof that the given key is not in the trie, or
+    // None if it is. The proof holds the nodes on the path of the key up to the
+    // point where it leaves the trie: an empty branch slot, a branch without a
+    // value, or a leaf or extension node whose path diverges from the key.
+    pub fn prove_absence(&self, key: &[u8]) -> Option<Proof> {
+        self.try_prove_absence(key).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_prove_absence(&self, key: &[u8]) -> Result<Option<Proof>, TrieError> {
+        let (proof, found) = self.prove_path(key)?;
+        Ok((!found).then_some(proof))
+    }
+
+    // ProveMany returns the proof nodes for all the given keys at once. Keys that
+    // are not in the trie are proven absent. Nodes shared by several paths, the
+    // root and the upper branches in particular, are only included once.
+    pub fn prove_many(&self, keys: &[&[u8]]) -> ProofDb {
+        self.try_prove_many(keys).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_prove_many(&self, keys: &[&[u8]]) -> Result<ProofDb, TrieError> {
+        let mut db = ProofDb::new();
+        for key in keys {
+            let (proof, _) = self.prove_path(key)?;
+            for node in proof.nodes {
+                db.put(node);
+            }
+        }
+        Ok(db)
+    }
+
+    // ProveRange proves the first and the last key of a range of the trie.
+    // The first key does not have to be in the trie, the keys in between are
+    // checked by verify_range_proof.
+    pub fn prove_range(&self, first: &[u8], last: &[u8]) -> ProofDb {
+        self.prove_many(&[first, last])
+    }
+
+    // prove_path collects the nodes on the path of the key and reports whether
+    // the key was found at the end of it, or fails if a node on the path cannot
+    // be loaded.
+    fn prove_path(&self, key: &[u8]) -> Result<(Proof, bool), TrieError> {
+        // proof := NewProofDB()
+        // node := t.root
+        // nibbles := FromBytes(key)
+        self.prove_from(&self.root, Nibble::from_bytes(key.to_vec()), Proof::new())
+    }
+
+    // prove_from is prove_path from the given node down, it starts over below
+    // a node loaded from the database.
+    fn prove_from(&self, mut node: &Node, mut nibbles: Vec<Nibble>, mut proof: Proof) -> Result<(Proof, bool), TrieError> {
+        loop {
+            // if IsEmptyNode(node) {
+            //   return nil, false
+            // }
+            if node.is_empty() {
+                return Ok((proof, false));
+            }
+
+            if let Node::Hash(hash) = node {
+                let loaded = self.resolve(hash)?;
+                return self.prove_from(&loaded, nibbles, proof);
+            }
+
+            // proof.Put(Hash(node), Serialize(node))
+            let encoded = node.serialize();
+            if proof.is_empty() || encoded.len() >= 32 {
+                proof.put(encoded);
+            }
+
+            match node {
+                Node::Empty | Node::Hash(_) => return Ok((proof, false)),
+
+                // if leaf, ok := node.(*LeafNode); ok {
+                //   matched := PrefixMatchedLen(leaf.Path, nibbles)
+                //   if matched != len(leaf.Path) || matched != len(nibbles) {
+                //     return nil, false
+                //   }
+                //
+                //   return proof, true
+                // }
+                Node::Leaf(leaf) => {
+                    let matched = Nibble::prefix_matched_len(&leaf.path, &nibbles);
+                    let found = matched == leaf.path.len() && matched == nibbles.len();
+                    return Ok((proof, found));
+                }
+
+                // if branch, ok := node.(*BranchNode); ok {
+                //   if len(nibbles) == 0 {
+                //     return proof, branch.HasValue()
+                //   }
+                //
+                //   b, remaining := nibbles[0], nibbles[1:]
+                //   nibbles = remaining
+                //   node = branch.Branches[b]
+                //   continue
+                // }
+                Node::Branch(branch) => {
+                    if nibbles.is_empty() {
+                        let found = branch.has_value();
+                        return Ok((proof, found));
+                    }
+                    let b = nibbles.remove(0);
+                    node = &branch.branches[b.to_usize()];
+                }
+
+                // if ext, ok := node.(*ExtensionNode); ok {
+                //   matched := PrefixMatchedLen(ext.Path, nibbles)
+                //   // E 01020304
+                //   //   010203
+                //   if matched < len(ext.Path) {
+                //     return nil, false
+                //   }
+                //
+                //   nibbles = nibbles[matched:]
+                //   node = ext.Next
+                //   continue
+                // }
+                Node::Extension(ext) => {
+                    let matched = Nibble::prefix_matched_len(&ext.path, &nibbles);
+                    if matched < ext.path.len() {
+                        return Ok((proof, false));
+                    }
+                    nibbles.drain(..matched);
+                    node = &ext.next;
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProofError {
+    // the proof does not contain the node with this hash
+    MissingNode([u8; 32]),
+    // a proof node is not valid RLP
+    Rlp(DecoderError),
+    // a proof node decodes but is not a leaf, extension or branch node
+    InvalidNode(&'static str),
+    // the keys and values of a range proof are not a valid range
+    InvalidRange(&'static str),
+    // the trie rebuilt from a range proof does not have the expected root
+    RootMismatch,
+}
+
+impl fmt::Display for ProofError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ProofError::MissingNode(hash) => write!(f, "proof node {} not found", hex::encode(hash)),
+            ProofError::Rlp(err) => write!(f, "invalid proof node rlp: {}", err),
+            ProofError::InvalidNode(reason) => write!(f, "invalid proof node: {}", reason),
+            ProofError::InvalidRange(reason) => write!(f, "invalid range: {}", reason),
+            ProofError::RootMismatch => write!(f, "root hash mismatch"),
+        }
+    }
+}
+
+impl std::error::Error for ProofError {}
+
+impl From<DecoderError> for ProofError {
+    fn from(err: DecoderError) -> Self {
+        match err {
+            // Node::decode reports well-formed RLP that is not a node as Custom
+            DecoderError::Custom(reason) => ProofError::InvalidNode(reason),
+            err => ProofError::Rlp(err),
+        }
+    }
+}
+
+// What a valid proof shows about a key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Membership {
+    // the key is in the trie with this value
+    Present(Vec<u8>),
+    // the key is not in the trie
+    Absent(Absence),
+}
+
+// Where the path of an absent key leaves the trie.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Absence {
+    // the root is the empty node hash
+    EmptyTrie,
+    // the next nibble of the key points to an empty branch slot
+    EmptyBranchSlot,
+    // the key ends at a branch node that has no value
+    EmptyBranchValue,
+    // the key reaches a leaf node whose path is different from the rest of the key
+    DivergingLeaf,
+    // the rest of the key does not start with the path of an extension node
+    DivergingExtension,
+}
+
+// // VerifyProof verify the proof for the given key under the given root hash using go-ethereum's VerifyProof implementation.
+// // It returns the value for the key if the proof is valid, otherwise error will be returned
+// func VerifyProof(rootHash []byte, key []byte, proof Proof) (value []byte, err error) {
+//   return trie.VerifyProof(common.BytesToHash(rootHash), key, proof)
+// }
+//
+// Unlike the Go version this does not depend on go-ethereum: it walks the proof
+// nodes from the root, following the key's nibbles. It returns Ok(Some(value))
+// if the key is in the trie, Ok(None) if the proof shows that the key is not in
+// the trie, and an error if the proof is incomplete or malformed.
+pub fn verify_proof(root: [u8; 32], key: &[u8], proof: &Proof) -> Result<Option<Vec<u8>>, ProofError> {
+    match verify_membership(root, key, proof)? {
+        Membership::Present(value) => Ok(Some(value)),
+        Membership::Absent(_) => Ok(None),
+    }
+}
+
+// VerifyMembership is VerifyProof telling why an absent key is not in the trie.
+pub fn verify_membership(root: [u8; 32], key: &[u8], proof: &Proof) -> Result<Membership, ProofError> {
+    walk_proof(root, key, |hash| proof.get(hash))
+}
+
+// VerifyMany verifies all the keys against the node set returned by ProveMany,
+// returning the value of each key, or None for the keys proven absent.
+pub fn verify_many(root: [u8; 32], keys: &[&[u8]], proof: &ProofDb) -> Result<Vec<Option<Vec<u8>>>, ProofError> {
+    keys.iter()
+        .map(|key| match walk_proof(root, key, |hash| proof.get(hash))? {
+            Membership::Present(value) => Ok(Some(value)),
+            Membership::Absent(_) => Ok(None),
+        })
+        .collect()
+}
+
+// VerifyRangeProof checks that the keys and values are exactly the content of
+// the trie from first_key up to the last key, as go-ethereum's
+// VerifyRangeProof does. The proof holds the edge proofs of first_key and of
+// the last key, see ProveRange. The trie is rebuilt from the nodes on the two
+// edge paths, with every subtrie in between replaced by the subtrie of the
+// given keys, and must hash to the root. An empty proof means the keys are the
+// whole trie. It returns whether the trie has more keys after the last one.
+pub fn verify_range_proof(
+    root: [u8; 32],
+    first_key: &[u8],
+    keys: &[&[u8]],
+    values: &[&[u8]],
+    proof: &ProofDb,
+) -> Result<bool, ProofError> {
+    if keys.len() != values.len() {
+        return Err(ProofError::InvalidRange("keys and values have different lengths"));
+    }
+    if keys.windows(2).any(|pair| pair[0] >= pair[1]) {
+        return Err(ProofError::InvalidRange("keys are not strictly increasing"));
+    }
+    if values.iter().any(|value| value.is_empty()) {
+        return Err(ProofError::InvalidRange("empty value"));
+    }
+
+    if proof.is_empty() {
+        let mut tr = Trie::new();
+        for (key, value) in keys.iter().zip(values) {
+            tr.put(key, value);
+        }
+        if tr.hash() != root {
+            return Err(ProofError::RootMismatch);
+        }
+        return Ok(false);
+    }
+
+    if keys.first().is_some_and(|key| *key < first_key) {
+        return Err(ProofError::InvalidRange("keys start before the first key"));
+    }
+
+    let left = Nibble::from_bytes(first_key.to_vec());
+    let right = match keys.last() {
+        Some(key) => Nibble::from_bytes(key.to_vec()),
+        None => left.clone(),
+    };
+    let mut range = RangeRebuilder {
+        proof,
+        left,
+        right,
+        entries: keys.iter().zip(values).map(|(key, value)| (Nibble::from_bytes(key.to_vec()), value.to_vec())).collect(),
+        consumed: 0,
+        has_more: false,
+    };
+
+    let rebuilt = if root == EMPTY_NODE_HASH {
+        Node::Empty
+    } else {
+        range.rebuild_node(Node::Hash(root), Vec::new())?
+    };
+    if rebuilt.hash() != root {
+        return Err(ProofError::RootMismatch);
+    }
+    if range.consumed != keys.len() {
+        return Err(ProofError::InvalidRange("keys outside the proven paths"));
+    }
+    Ok(range.has_more)
+}
+
+// load replaces the hashed nodes under node that are in the proof by their
+// decoded content.
+fn load(node: Node, proof: &ProofDb) -> Result<Node, ProofError> {
+    match node {
+        Node::Hash(hash) => match proof.get(&hash) {
+            Some(encoded) => load(Node::decode(encoded)?, proof),
+            None => Ok(Node::Hash(hash)),
+        },
+        Node::Extension(ext) => Ok(Node::Extension(ExtensionNode::new(ext.path, load(*ext.next, proof)?))),
+        Node::Branch(mut branch) => {
+            for child in branch.branches.iter_mut() {
+                *child = load(std::mem::take(child), proof)?;
+            }
+            Ok(Node::Branch(branch))
+        }
+        node => Ok(node),
+    }
+}
+
+// RangeRebuilder rebuilds the nodes on the edge paths of a range proof, taking
+// the content between the edges from the keys of the range.
+struct RangeRebuilder<'a> {
+    proof: &'a ProofDb,
+    // the nibbles of the first and the last key of the range
+    left: Vec<Nibble>,
+    right: Vec<Nibble>,
+    entries: Vec<(Vec<Nibble>, Vec<u8>)>,
+    // how many entries ended up in the rebuilt trie
+    consumed: usize,
+    // whether a key after the last key was seen
+    has_more: bool,
+}
+
+impl RangeRebuilder<'_> {
+    // rebuild_child returns the new subtrie under path.
+    fn rebuild_child(&mut self, child: Node, path: Vec<Nibble>) -> Result<Node, ProofError> {
+        if self.left.starts_with(&path) || self.right.starts_with(&path) {
+            return self.rebuild_node(child, path);
+        }
+
+        // the subtrie is off the edge paths, so it is either entirely outside
+        // of the range and kept, or entirely inside and rebuilt
+        if path < self.left || path > self.right {
+            if !child.is_empty() && path > self.right {
+                self.has_more = true;
+            }
+            return Ok(child);
+        }
+        Ok(self.build(&path))
+    }
+
+    // rebuild_node returns the new node for a node on an edge path.
+    fn rebuild_node(&mut self, node: Node, path: Vec<Nibble>) -> Result<Node, ProofError> {
+        match node {
+            Node::Empty => Ok(Node::Empty),
+
+            Node::Hash(hash) => {
+                let encoded = self.proof.get(&hash).ok_or(ProofError::MissingNode(hash))?;
+                self.rebuild_node(Node::decode(encoded)?, path)
+            }
+
+            Node::Leaf(leaf) => {
+                let mut full = path.clone();
+                full.extend(&leaf.path);
+                if full >= self.left && full <= self.right {
+                    return Ok(self.build(&path));
+                }
+                if full > self.right {
+                    self.has_more = true;
+                }
+                Ok(Node::Leaf(leaf))
+            }
+
+            Node::Extension(ext) => {
+                let mut full = path;
+                full.extend(&ext.path);
+                let next = self.rebuild_child(*ext.next, full)?;
+                if next.is_empty() {
+                    return Ok(Node::Empty);
+                }
+                Ok(Node::Extension(ExtensionNode::new(ext.path, next)))
+            }
+
+            Node::Branch(mut branch) => {
+                branch.cache.clear();
+                for i in 0..16 {
+                    let mut child_path = path.clone();
+                    child_path.push(Nibble(i as u8));
+                    let child = std::mem::take(&mut branch.branches[i]);
+                    branch.branches[i] = self.rebuild_child(child, child_path)?;
+                }
+                if path >= self.left && path <= self.right {
+                    branch.value = self.take(&path);
+                }
+                Ok(Node::Branch(branch))
+            }
+        }
+    }
+
+    // build puts the entries under path into a new trie and returns its root.
+    fn build(&mut self, path: &[Nibble]) -> Node {
+        let mut tr = Trie::new();
+        for (key, value) in self.entries.iter().filter(|(key, _)| key.starts_with(path)) {
+            tr.put_nibbles(key[path.len()..].to_vec(), value).expect("a new trie has no unloaded subtries");
+            self.consumed += 1;
+        }
+        tr.root
+    }
+
+    // take returns the value of the entry whose key is exactly path.
+    fn take(&mut self, path: &[Nibble]) -> Option<Vec<u8>> {
+        let (_, value) = self.entries.iter().find(|(key, _)| key == path)?;
+        self.consumed += 1;
+        Some(value.clone())
+    }
+}
+
+// walk_proof follows the key from the root, looking up hashed nodes with get.
+fn walk_proof<'a, F>(root: [u8; 32], key: &[u8], get: F) -> Result<Membership, ProofError>
+where
+    F: Fn(&[u8; 32]) -> Option<&'a [u8]>,
+{
+    if root == EMPTY_NODE_HASH {
+        return Ok(Membership::Absent(Absence::EmptyTrie));
+    }
+
+    let mut node = Node::Hash(root);
+    let mut nibbles = Nibble::from_bytes(key.to_vec());
+
+    loop {
+        match node {
+            // only a branch slot can be empty
+            Node::Empty => return Ok(Membership::Absent(Absence::EmptyBranchSlot)),
+
+            Node::Hash(hash) => {
+                node = Node::decode(get(&hash).ok_or(ProofError::MissingNode(hash))?)?;
+            }
+
+            Node::Leaf(leaf) => {
+                if leaf.path != nibbles {
+                    return Ok(Membership::Absent(Absence::DivergingLeaf));
+                }
+                return Ok(Membership::Present(leaf.value));
+            }
+
+            Node::Extension(ext) => {
+                if !nibbles.starts_with(&ext.path) {
+                    return Ok(Membership::Absent(Absence::DivergingExtension));
+                }
+                nibbles.drain(..ext.path.len());
+                node = *ext.next;
+            }
+
+            Node::Branch(mut branch) => {
+                if nibbles.is_empty() {
+                    return match branch.value {
+                        Some(value) => Ok(Membership::Present(value)),
+                        None => Ok(Membership::Absent(Absence::EmptyBranchValue)),
+                    };
+                }
+                let b = nibbles.remove(0);
+                node = std::mem::take(&mut branch.branches[b.to_usize()]);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::leaf::LeafNode;
+
+    // func TestProveAndVerifyProof(t *testing.T) {
+    //   t.Run("should not generate proof for non-exist key", func(t *testing.T) {
+    //     tr := NewTrie()
+    //     tr.Put([]byte{1, 2, 3}, []byte("hello"))
+    //     tr.Put([]byte{1, 2, 3, 4, 5}, []byte("world"))
+    //     notExistKey := []byte{1, 2, 3, 4}
+    //     _, ok := tr.Prove(notExistKey)
+    //     require.False(t, ok)
+    //   })
+    #[test]
+    fn test_prove_non_exist_key() {
+        let mut tr = Trie::new();
+        assert_eq!(tr.prove(&[1, 2, 3]), None);
+
+        tr.put(&[1, 2, 3], b"hello");
+        tr.put(&[1, 2, 3, 4, 5], b"world");
+        assert_eq!(tr.prove(&[1, 2, 3, 4]), None);
+    }
+
+    #[test]
+    fn test_prove_orders_nodes_from_root_to_leaf() {
+        let mut tr = Trie::new();
+        tr.put(&[1, 2, 3], b"hello");
+        tr.put(&[1, 2, 3, 4, 5], b"world");
+
+        // E 010203 -> B [0: L 405 world] hello
+        let proof = tr.prove(&[1, 2, 3]).unwrap();
+        let Node::Extension(ext) = &tr.root else { panic!("root is not an extension node") };
+        assert_eq!(vec![tr.root.serialize(), ext.next.serialize()], proof.serialize());
+        assert_eq!(tr.hash(), keccak256(&proof.nodes()[0]).to_vec());
+        assert!(proof.has(&ext.next.hash()));
+
+        // the leaf is embedded in the branch, so it is not part of the proof
+        let proof = tr.prove(&[1, 2, 3, 4, 5]).unwrap();
+        assert_eq!(2, proof.len());
+    }
+
+    #[test]
+    fn test_prove_always_includes_root() {
+        let mut tr = Trie::new();
+        tr.put(&[1], b"a");
+        assert!(tr.root.serialize().len() < 32);
+
+        let proof = tr.prove(&[1]).unwrap();
+        assert_eq!(vec![tr.root.serialize()], proof.serialize());
+    }
+
+    fn root(tr: &Trie) -> [u8; 32] {
+        tr.hash().try_into().unwrap()
+    }
+
+    //   t.Run("should generate a proof for an existing key, the proof can be verified with the merkle root hash", func(t *testing.T) {
+    //     tr := NewTrie()
+    //     tr.Put([]byte{1, 2, 3}, []byte("hello"))
+    //     tr.Put([]byte{1, 2, 3, 4, 5}, []byte("world"))
+    //
+    //     key := []byte{1, 2, 3}
+    //     proof, ok := tr.Prove(key)
+    //     require.True(t, ok)
+    //
+    //     rootHash := tr.Hash()
+    //
+    //     // verify the proof with the root hash, the key in question and its proof
+    //     val, err := VerifyProof(rootHash, key, proof)
+    //     require.NoError(t, err)
+    //
+    //     // when the verification has passed, it should return the correct value for the key
+    //     require.Equal(t, []byte("hello"), val)
+    //   })
+    #[test]
+    fn test_verify_proof() {
+        let mut tr = Trie::new();
+        tr.put(&[1, 2, 3], b"hello");
+        tr.put(&[1, 2, 3, 4, 5], b"world");
+
+        let key = [1, 2, 3];
+        let proof = tr.prove(&key).unwrap();
+
+        // verify the proof with the root hash, the key in question and its proof
+        let val = verify_proof(root(&tr), &key, &proof).unwrap();
+
+        // when the verification has passed, it should return the correct value for the key
+        assert_eq!(Some(b"hello".to_vec()), val);
+
+        // the value behind the embedded leaf is proven by the same nodes
+        let val = verify_proof(root(&tr), &[1, 2, 3, 4, 5], &proof).unwrap();
+        assert_eq!(Some(b"world".to_vec()), val);
+    }
+
+    //   t.Run("should fail the verification of the trie was updated", func(t *testing.T) {
+    //     tr := NewTrie()
+    //     tr.Put([]byte{1, 2, 3}, []byte("hello"))
+    //     tr.Put([]byte{1, 2, 3, 4, 5}, []byte("world"))
+    //
+    //     // the hash was taken before the trie was updated
+    //     rootHash := tr.Hash()
+    //
+    //     // the proof was generated after the trie was updated
+    //     tr.Put([]byte{5, 6, 7}, []byte("trie"))
+    //     key := []byte{1, 2, 3}
+    //     proof, ok := tr.Prove(key)
+    //     require.True(t, ok)
+    //
+    //     // should fail the verification since the merkle root hash doesn't match
+    //     _, err := VerifyProof(rootHash, key, proof)
+    //     require.Error(t, err)
+    //   })
+    // }
+    #[test]
+    fn test_verify_proof_after_update() {
+        let mut tr = Trie::new();
+        tr.put(&[1, 2, 3], b"hello");
+        tr.put(&[1, 2, 3, 4, 5], b"world");
+
+        // the hash was taken before the trie was updated
+        let root_hash = root(&tr);
+
+        // the proof was generated after the trie was updated
+        tr.put(&[5, 6, 7], b"trie");
+        let key = [1, 2, 3];
+        let proof = tr.prove(&key).unwrap();
+
+        // should fail the verification since the merkle root hash doesn't match
+        assert_eq!(Err(ProofError::MissingNode(root_hash)), verify_proof(root_hash, &key, &proof));
+    }
+
+    #[test]
+    fn test_verify_proof_absent_key() {
+        let mut tr = Trie::new();
+        tr.put(&[1, 2, 3], b"hello");
+        tr.put(&[1, 2, 3, 4, 5], b"world");
+        let proof = tr.prove(&[1, 2, 3]).unwrap();
+
+        // diverges inside the embedded leaf
+        assert_eq!(Ok(None), verify_proof(root(&tr), &[1, 2, 3, 4], &proof));
+        // ends at an empty branch slot
+        assert_eq!(Ok(None), verify_proof(root(&tr), &[1, 2, 3, 0x50], &proof));
+        // diverges from the extension
+        assert_eq!(Ok(None), verify_proof(root(&tr), &[1, 2], &proof));
+
+        assert_eq!(Ok(None), verify_proof(EMPTY_NODE_HASH, &[1, 2, 3], &Proof::new()));
+    }
+
+    #[test]
+    fn test_verify_proof_invalid() {
+        let mut tr = Trie::new();
+        for i in 0..100u8 {
+            tr.put(&[i, i.wrapping_mul(7)], &[i; 40]);
+        }
+        let key = [42, 42u8.wrapping_mul(7)];
+        let proof = tr.prove(&key).unwrap();
+        assert!(proof.len() > 1);
+
+        // a node below the root is missing
+        let truncated = Proof::from_nodes(proof.nodes()[..proof.len() - 1].to_vec());
+        assert!(matches!(verify_proof(root(&tr), &key, &truncated), Err(ProofError::MissingNode(_))));
+
+        // a node was tampered with, so its hash does not match anymore
+        let mut nodes = proof.serialize();
+        let last = nodes.last_mut().unwrap();
+        *last.last_mut().unwrap() ^= 1;
+        assert!(matches!(verify_proof(root(&tr), &key, &Proof::from_nodes(nodes)), Err(ProofError::MissingNode(_))));
+
+        // the root node is not a trie node
+        let garbage = vec![rlp::encode_list::<Vec<u8>, _>(&[vec![1], vec![2], vec![3]]).to_vec()];
+        let garbage_root = keccak256(&garbage[0]);
+        assert_eq!(
+            Err(ProofError::InvalidNode("unexpected number of items")),
+            verify_proof(garbage_root, &key, &Proof::from_nodes(garbage))
+        );
+    }
+
+    #[test]
+    fn test_prove_and_verify_every_key() {
+        let mut tr = Trie::new();
+        for i in 0..300u32 {
+            tr.put(&rlp::encode(&i), format!("value-{}", i).as_bytes());
+        }
+
+        for i in 0..300u32 {
+            let key = rlp::encode(&i);
+            let proof = tr.prove(&key).unwrap();
+            let val = verify_proof(root(&tr), &key, &proof).unwrap();
+            assert_eq!(Some(format!("value-{}", i).into_bytes()), val);
+        }
+    }
+
+    #[test]
+    fn test_prove_absence() {
+        let mut tr = Trie::new();
+        assert_eq!(Some(Proof::new()), tr.prove_absence(&[1, 2, 3]));
+        assert_eq!(
+            Ok(Membership::Absent(Absence::EmptyTrie)),
+            verify_membership(EMPTY_NODE_HASH, &[1, 2, 3], &Proof::new())
+        );
+
+        tr.put(&[1, 2, 3], b"hello");
+        tr.put(&[1, 2, 3, 4, 5], b"world");
+        tr.put(&[1, 2, 3, 0x60], &[7; 40]);
+
+        // E 010203 -> B [0: L 405 world, 6: L 0 ...] hello
+        assert_eq!(None, tr.prove_absence(&[1, 2, 3]));
+        assert_eq!(None, tr.prove_absence(&[1, 2, 3, 4, 5]));
+
+        let cases: Vec<(&[u8], Absence)> = vec![
+            (&[1, 2, 3, 4], Absence::DivergingLeaf),
+            (&[1, 2, 3, 0x61], Absence::DivergingLeaf),
+            (&[1, 2, 3, 0x50], Absence::EmptyBranchSlot),
+            (&[1, 2], Absence::DivergingExtension),
+            (&[2], Absence::DivergingExtension),
+        ];
+        for (key, reason) in cases {
+            let proof = tr.prove_absence(key).unwrap();
+            assert_eq!(Ok(Membership::Absent(reason)), verify_membership(root(&tr), key, &proof));
+            assert_eq!(Ok(None), verify_proof(root(&tr), key, &proof));
+        }
+
+        // B [1: L 2 hello, 3: L 4 world] without value
+        let mut tr = Trie::new();
+        tr.put(&[0x12], b"hello");
+        tr.put(&[0x34], b"world");
+        let proof = tr.prove_absence(&[]).unwrap();
+        assert_eq!(Ok(Membership::Absent(Absence::EmptyBranchValue)), verify_membership(root(&tr), &[], &proof));
+    }
+
+    #[test]
+    fn test_prove_index_past_the_end() {
+        let mut tr = Trie::new();
+        for i in 0..150u32 {
+            tr.put(&rlp::encode(&i), &[i as u8; 100]);
+        }
+
+        let key = rlp::encode(&150u32);
+        let proof = tr.prove_absence(&key).unwrap();
+        assert!(matches!(verify_membership(root(&tr), &key, &proof), Ok(Membership::Absent(_))));
+
+        // the absence proof does not hold against a trie that has the key
+        tr.put(&key, b"receipt");
+        assert!(verify_membership(root(&tr), &key, &proof).is_err());
+    }
+
+    #[test]
+    fn test_prove_many() {
+        let mut tr = Trie::new();
+        for i in 0..100u32 {
+            tr.put(&rlp::encode(&i), &[i as u8; 40]);
+        }
+
+        let keys: Vec<Vec<u8>> = (0..100u32).map(|i| rlp::encode(&i).to_vec()).collect();
+        let mut keys: Vec<&[u8]> = keys.iter().map(|key| key.as_slice()).collect();
+        let absent = rlp::encode(&100u32);
+        keys.push(&absent);
+
+        let db = tr.prove_many(&keys);
+        let values = verify_many(root(&tr), &keys, &db).unwrap();
+        for (i, value) in values.iter().enumerate().take(100) {
+            assert_eq!(&Some(vec![i as u8; 40]), value);
+        }
+        assert_eq!(None, values[100]);
+
+        // the shared upper nodes are only shipped once
+        let separate: usize = keys.iter().map(|key| tr.prove_path(key).unwrap().0.nodes.iter().map(Vec::len).sum::<usize>()).sum();
+        let shared: usize = db.serialize().iter().map(Vec::len).sum();
+        assert!(shared * 2 < separate, "{} vs {}", shared, separate);
+
+        // every node is needed
+        let mut nodes = db.serialize();
+        nodes.pop();
+        assert!(matches!(verify_many(root(&tr), &keys, &ProofDb::from_nodes(nodes)), Err(ProofError::MissingNode(_))));
+    }
+
+    // range_trie has the keys 0, 3, 6, ... as big endian u16, with values of
+    // varying size so that some nodes are embedded in their parents.
+    fn range_trie() -> (Trie, Vec<Vec<u8>>, Vec<Vec<u8>>) {
+        let mut tr = Trie::new();
+        let keys: Vec<Vec<u8>> = (0..200u16).map(|i| (i * 3).to_be_bytes().to_vec()).collect();
+        let values: Vec<Vec<u8>> = (0..200usize).map(|i| vec![i as u8 + 1; 1 + i % 40]).collect();
+        for (key, value) in keys.iter().zip(&values) {
+            tr.put(key, value);
+        }
+        (tr, keys, values)
+    }
+
+    fn slices(items: &[Vec<u8>]) -> Vec<&[u8]> {
+        items.iter().map(|item| item.as_slice()).collect()
+    }
+
+    #[test]
+    fn test_prove_range() {
+        let (tr, keys, values) = range_trie();
+
+        for (start, end) in [(0, 200), (0, 1), (5, 6), (17, 90), (100, 199), (150, 200), (0, 16), (16, 32)] {
+            let proof = tr.prove_range(&keys[start], &keys[end - 1]);
+            let more = verify_range_proof(root(&tr), &keys[start], &slices(&keys[start..end]), &slices(&values[start..end]), &proof);
+            assert_eq!(Ok(end < 200), more, "range {}..{}", start, end);
+        }
+    }
+
+    #[test]
+    fn test_prove_range_absent_first_key() {
+        let (tr, keys, values) = range_trie();
+
+        // 31 is between the keys 30 and 33
+        let first = 31u16.to_be_bytes();
+        let proof = tr.prove_range(&first, &keys[20]);
+        assert_eq!(Ok(true), verify_range_proof(root(&tr), &first, &slices(&keys[11..21]), &slices(&values[11..21]), &proof));
+
+        // the key 33 in the range is left out
+        assert_eq!(
+            Err(ProofError::RootMismatch),
+            verify_range_proof(root(&tr), &first, &slices(&keys[12..21]), &slices(&values[12..21]), &proof)
+        );
+
+        // nothing after the end of the trie
+        let first = 600u16.to_be_bytes();
+        let proof = tr.prove_range(&first, &first);
+        assert_eq!(Ok(false), verify_range_proof(root(&tr), &first, &[], &[], &proof));
+    }
+
+    #[test]
+    fn test_prove_range_whole_trie() {
+        let (tr, keys, values) = range_trie();
+        assert_eq!(Ok(false), verify_range_proof(root(&tr), &[], &slices(&keys), &slices(&values), &ProofDb::new()));
+        assert_eq!(
+            Err(ProofError::RootMismatch),
+            verify_range_proof(root(&tr), &[], &slices(&keys[1..]), &slices(&values[1..]), &ProofDb::new())
+        );
+    }
+
+    #[test]
+    fn test_prove_range_invalid() {
+        let (tr, keys, values) = range_trie();
+        let proof = tr.prove_range(&keys[10], &keys[59]);
+        let verify = |keys: &[Vec<u8>], values: &[Vec<u8>]| {
+            verify_range_proof(root(&tr), &keys[0], &slices(keys), &slices(values), &proof)
+        };
+        assert_eq!(Ok(true), verify(&keys[10..60], &values[10..60]));
+
+        // a key in the middle is missing
+        let mut missing = (keys[10..60].to_vec(), values[10..60].to_vec());
+        missing.0.remove(25);
+        missing.1.remove(25);
+        assert_eq!(Err(ProofError::RootMismatch), verify(&missing.0, &missing.1));
+
+        // a value is changed
+        let mut changed = values[10..60].to_vec();
+        changed[30] = b"forged".to_vec();
+        assert_eq!(Err(ProofError::RootMismatch), verify(&keys[10..60], &changed));
+
+        // a key that is not in the trie is added
+        let mut extra = (keys[10..60].to_vec(), values[10..60].to_vec());
+        extra.0.insert(21, 91u16.to_be_bytes().to_vec());
+        extra.1.insert(21, b"extra".to_vec());
+        assert_eq!(Err(ProofError::RootMismatch), verify(&extra.0, &extra.1));
+
+        // the keys must be sorted
+        let mut unsorted = keys[10..60].to_vec();
+        unsorted.swap(3, 4);
+        assert!(matches!(verify(&unsorted, &values[10..60]), Err(ProofError::InvalidRange(_))));
+
+        // the edge proof of the last key is needed
+        let proof = tr.prove(&keys[10]).unwrap().into();
+        assert!(matches!(
+            verify_range_proof(root(&tr), &keys[10], &slices(&keys[10..60]), &slices(&values[10..60]), &proof),
+            Err(ProofError::MissingNode(_))
+        ));
+    }
+
+    #[test]
+    fn test_from_proof() {
+        let (mut tr, keys, values) = range_trie();
+        let absent = 31u16.to_be_bytes();
+        let db = tr.prove_many(&[&keys[10], &keys[50], &absent]);
+
+        let mut partial = Trie::from_proof(root(&tr), &db).unwrap();
+        assert_eq!(tr.hash(), partial.hash());
+
+        assert_eq!(Some(values[10].clone()), partial.try_get(&keys[10]).unwrap());
+        assert_eq!(None, partial.try_get(&absent).unwrap());
+        assert!(matches!(partial.try_get(&keys[70]), Err(TrieError::MissingNode(_))));
+
+        // updates on the proven paths give the same root as on the full trie
+        tr.put(&keys[10], b"updated");
+        partial.try_put(&keys[10], b"updated").unwrap();
+        assert_eq!(tr.hash(), partial.hash());
+
+        tr.put(&absent, b"inserted");
+        partial.try_put(&absent, b"inserted").unwrap();
+        assert_eq!(tr.hash(), partial.hash());
+
+        assert!(tr.delete(&keys[50]));
+        assert!(partial.try_delete(&keys[50]).unwrap());
+        assert_eq!(tr.hash(), partial.hash());
+
+        // updates on unproven paths fail and leave the trie as it was
+        assert!(matches!(partial.try_put(&keys[70], b"updated"), Err(TrieError::MissingNode(_))));
+        assert!(matches!(partial.try_delete(&keys[70]), Err(TrieError::MissingNode(_))));
+        assert_eq!(tr.hash(), partial.hash());
+    }
+
+    #[test]
+    fn test_from_proof_delete_collapse() {
+        let mut tr = Trie::new();
+        tr.put(&[0x10], &[1; 40]);
+        tr.put(&[0x20], &[2; 40]);
+
+        // deleting 0x10 merges the branch into the leaf of 0x20, which is not
+        // in the proof
+        let mut partial = Trie::from_proof(root(&tr), &tr.prove_many(&[&[0x10]])).unwrap();
+        let leaf = LeafNode::new_from_nibble_bytes(&[0], &[2; 40]).unwrap();
+        let hash: [u8; 32] = leaf.hash().try_into().unwrap();
+        assert!(matches!(partial.try_delete(&[0x10]), Err(TrieError::MissingNode(missing)) if missing == hash));
+        assert_eq!(tr.hash(), partial.hash());
+
+        let mut partial = Trie::from_proof(root(&tr), &tr.prove_many(&[&[0x10], &[0x20]])).unwrap();
+        assert!(partial.try_delete(&[0x10]).unwrap());
+        tr.delete(&[0x10]);
+        assert_eq!(tr.hash(), partial.hash());
+
+        // deleting an absent key next to 0x10 changes nothing, so it does not
+        // need the leaf of 0x20
+        let mut tr = Trie::new();
+        tr.put(&[0x10; 4], &[1; 40]);
+        tr.put(&[0x20; 4], &[2; 40]);
+        let mut partial = Trie::from_proof(root(&tr), &tr.prove_many(&[&[0x10; 4]])).unwrap();
+        assert_eq!(None, partial.try_get(&[0x1f, 0, 0, 0]).unwrap());
+        assert!(!partial.try_delete(&[0x1f, 0, 0, 0]).unwrap());
+        assert!(!partial.try_delete(&[0x10, 0, 0, 0]).unwrap());
+        assert_eq!(tr.hash(), partial.hash());
+    }
+
+    #[test]
+    fn test_from_proof_missing_root() {
+        let (tr, keys, _) = range_trie();
+        let other = Trie::new();
+        assert_eq!(Ok(other.hash()), Trie::from_proof(root(&other), &ProofDb::new()).map(|tr| tr.hash()));
+        assert_eq!(
+            Err(ProofError::MissingNode(root(&tr))),
+            Trie::from_proof(root(&tr), &ProofDb::new()).map(|tr| tr.hash())
+        );
+        assert!(Trie::from_proof(root(&tr), &tr.prove_many(&[&keys[0]])).is_ok());
+    }
+}
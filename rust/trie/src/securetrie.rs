@@ -0,0 +1,203 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::crypto::keccak256;
+use crate::db::NodeDb;
+use crate::proof::Proof;
+use crate::trie::{Entry, Trie, TrieError};
+
+// SecureTrie wraps a Trie whose keys are the keccak256 hash of the keys it is
+// given, like the state trie keyed by keccak256(address) and the storage tries
+// keyed by keccak256(slot). Hashing keeps the paths short and balanced, so the
+// original keys are lost unless the preimage store records them.
+#[derive(Debug, Clone, Default)]
+pub struct SecureTrie {
+    trie: Trie,
+    // hashed key -> original key, None when preimages are not recorded
+    preimages: Option<HashMap<[u8; 32], Vec<u8>>>,
+}
+
+impl SecureTrie {
+    pub fn new() -> SecureTrie {
+        SecureTrie::default()
+    }
+
+    // with_preimages returns an empty trie that records the original keys,
+    // so that entries can return them.
+    pub fn with_preimages() -> SecureTrie {
+        SecureTrie { trie: Trie::new(), preimages: Some(HashMap::new()) }
+    }
+
+    // Open loads the trie with the given root from db, see Trie::open. The
+    // preimages of the keys already in the trie are not known.
+    pub fn open(db: Arc<dyn NodeDb>, root: [u8; 32]) -> Result<SecureTrie, TrieError> {
+        Ok(SecureTrie { trie: Trie::open(db, root)?, preimages: None })
+    }
+
+    // trie returns the underlying trie, whose keys are the hashed keys.
+    pub fn trie(&self) -> &Trie {
+        &self.trie
+    }
+
+    pub fn hash(&self) -> Vec<u8> {
+        self.trie.hash()
+    }
+
+    pub fn commit(&mut self) -> Result<[u8; 32], TrieError> {
+        self.trie.commit()
+    }
+
+    pub fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.trie.get(&keccak256(key))
+    }
+
+    pub fn try_get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, TrieError> {
+        self.trie.try_get(&keccak256(key))
+    }
+
+    pub fn put(&mut self, key: &[u8], value: &[u8]) {
+        self.try_put(key, value).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_put(&mut self, key: &[u8], value: &[u8]) -> Result<(), TrieError> {
+        let hashed = keccak256(key);
+        self.trie.try_put(&hashed, value)?;
+        if let Some(preimages) = &mut self.preimages {
+            preimages.insert(hashed, key.to_vec());
+        }
+        Ok(())
+    }
+
+    pub fn delete(&mut self, key: &[u8]) -> bool {
+        self.trie.delete(&keccak256(key))
+    }
+
+    pub fn try_delete(&mut self, key: &[u8]) -> Result<bool, TrieError> {
+        self.trie.try_delete(&keccak256(key))
+    }
+
+    // Prove returns the proof of the hashed key, which is verified with
+    // keccak256(key) as the key, as the eth_getProof proofs are.
+    pub fn prove(&self, key: &[u8]) -> Option<Proof> {
+        self.trie.prove(&keccak256(key))
+    }
+
+    pub fn prove_absence(&self, key: &[u8]) -> Option<Proof> {
+        self.trie.prove_absence(&keccak256(key))
+    }
+
+    pub fn try_prove(&self, key: &[u8]) -> Result<Option<Proof>, TrieError> {
+        self.trie.try_prove(&keccak256(key))
+    }
+
+    pub fn try_prove_absence(&self, key: &[u8]) -> Result<Option<Proof>, TrieError> {
+        self.trie.try_prove_absence(&keccak256(key))
+    }
+
+    // GetKey returns the original key of the hashed key, if it was recorded.
+    pub fn get_key(&self, hashed: &[u8; 32]) -> Option<&[u8]> {
+        self.preimages.as_ref()?.get(hashed).map(Vec::as_slice)
+    }
+
+    // Entries returns the original keys and their values, in the order of the
+    // hashed keys. It panics when a node cannot be loaded or a preimage is
+    // missing.
+    pub fn entries(&self) -> Vec<Entry> {
+        self.try_entries().unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_entries(&self) -> Result<Vec<Entry>, TrieError> {
+        self.trie
+            .try_entries()?
+            .into_iter()
+            .map(|(hashed, value)| {
+                let hashed: [u8; 32] = hashed.try_into().expect("hashed keys are 32 bytes");
+                let key = self.get_key(&hashed).ok_or(TrieError::MissingPreimage(hashed))?;
+                Ok((key.to_vec(), value))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::MemoryDb;
+    use crate::hexutil;
+    use crate::storage_proof::EthGetProofResponse;
+
+    // the storage of contract 0xcca577ee56d30a444c73f8fc8d5ce34ed1c7da8b, see
+    // storage_proof_slot_0.json and storage_proof_slot_1.json
+    fn storage() -> SecureTrie {
+        let mut tr = SecureTrie::with_preimages();
+        // owner address
+        tr.put(&[0; 32], &rlp::encode(&hexutil::decode("0xde74da73d5102a796559933296c73e7d1c6f37fb").unwrap()));
+        // last completed migration
+        let mut slot_1 = [0; 32];
+        slot_1[31] = 1;
+        tr.put(&slot_1, &rlp::encode(&vec![0x02u8]));
+        tr
+    }
+
+    #[test]
+    fn test_secure_trie_storage_proof() {
+        let tr = storage();
+        for fixture in [
+            include_str!("../../../storage_proof_slot_0.json"),
+            include_str!("../../../storage_proof_slot_1.json"),
+        ] {
+            let result = serde_json::from_str::<EthGetProofResponse>(fixture).unwrap().result;
+            assert_eq!(result.storage_hash.to_vec(), tr.hash());
+
+            let storage = &result.storage_proof[0];
+            assert_eq!(storage.proof, tr.prove(&storage.slot().unwrap()).unwrap().serialize());
+            assert_eq!(Some(rlp::encode(&storage.value).to_vec()), tr.get(&storage.slot().unwrap()));
+        }
+    }
+
+    #[test]
+    fn test_secure_trie_hashes_keys() {
+        let mut tr = storage();
+        let mut plain = Trie::new();
+        for (key, value) in tr.entries() {
+            plain.put(&keccak256(&key), &value);
+        }
+        assert_eq!(plain.hash(), tr.hash());
+        assert_eq!(plain.entries(), tr.trie().entries());
+
+        assert_eq!(None, tr.get(&[0; 31]));
+        assert!(tr.prove_absence(&[0; 31]).is_some());
+        assert!(tr.delete(&[0; 32]));
+        assert!(!tr.delete(&[0; 32]));
+        assert_eq!(None, tr.get(&[0; 32]));
+        assert_eq!(1, tr.entries().len());
+    }
+
+    #[test]
+    fn test_secure_trie_preimages() {
+        let mut tr = SecureTrie::with_preimages();
+        let keys: Vec<Vec<u8>> = (0..100u32).map(|i| i.to_be_bytes().to_vec()).collect();
+        for key in &keys {
+            tr.put(key, key);
+        }
+        let entries = tr.entries();
+        assert_eq!(keys.len(), entries.len());
+        assert!(entries.iter().all(|(key, value)| key == value));
+        // in the order of the hashed keys
+        assert!(entries.windows(2).all(|pair| keccak256(&pair[0].0) < keccak256(&pair[1].0)));
+        assert_eq!(Some(&keys[7][..]), tr.get_key(&keccak256(&keys[7])));
+
+        // without the preimages only the hashed keys are known
+        let db: Arc<dyn NodeDb> = Arc::new(MemoryDb::new());
+        let mut committed = SecureTrie::open(db.clone(), crate::EMPTY_NODE_HASH).unwrap();
+        for key in &keys {
+            committed.put(key, key);
+        }
+        let root = committed.commit().unwrap();
+        let opened = SecureTrie::open(db, root).unwrap();
+        assert_eq!(Some(keys[7].clone()), opened.get(&keys[7]));
+        assert_eq!(None, opened.get_key(&keccak256(&keys[7])));
+        let first: [u8; 32] = tr.trie().entries()[0].0.clone().try_into().unwrap();
+        assert!(matches!(opened.try_entries(), Err(TrieError::MissingPreimage(hashed)) if hashed == first));
+    }
+}
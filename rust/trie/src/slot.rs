@@ -0,0 +1,765 @@
+use crate::crypto::keccak256;
+
+// func GetSlotForMapKey(keyInMap []byte, slotIndexForMap int) [32]byte {
+// 	return crypto.Keccak256Hash(
+// 		keyInMap,
+// 		common.LeftPadBytes(big.NewInt(int64(slotIndexForMap)).Bytes(), 32),
+// 	)
+// }
+pub fn get_slot_for_map_key(key_in_map: &[u8], slot_index_for_map: u64) -> [u8; 32] {
+    map_slot(key_in_map, &slot_index(slot_index_for_map))
+}
+
+// func GetSlotForERC20TokenHolder(slotIndexForHoldersMap int, tokenHolder common.Address) [32]byte {
+// 	return GetSlotForMapKey(common.LeftPadBytes(tokenHolder[:], 32), slotIndexForHoldersMap)
+// }
+pub fn get_slot_for_erc20_token_holder(slot_index_for_holders_map: u64, token_holder: &[u8; 20]) -> [u8; 32] {
+    get_slot_for_map_key(&left_pad(token_holder), slot_index_for_holders_map)
+}
+
+// func GetSlotForArrayItem(slotIndexForArray int, indexInArray int, itemSize int) [32]byte {
+// 	bytes := crypto.Keccak256Hash(common.LeftPadBytes(big.NewInt(int64(slotIndexForArray)).Bytes(), 32))
+// 	arrayPos := new(big.Int).SetBytes(bytes[:])
+// 	itemPos := arrayPos.Add(arrayPos, big.NewInt(int64(indexInArray*itemSize)))
+// 	var pos [32]byte
+// 	copy(pos[:], itemPos.Bytes()[:32])
+//
+// 	return pos
+// }
+//
+// item_size is the number of slots of an item. The position wraps around
+// 2^256 like the EVM does, where the Go version cannot handle a position
+// with leading zero bytes.
+pub fn get_slot_for_array_item(slot_index_for_array: u64, index_in_array: u64, item_size: u64) -> [u8; 32] {
+    add(keccak256(&slot_index(slot_index_for_array)), index_in_array as u128 * item_size as u128)
+}
+
+// func GetKittySlot(slotIndexForKitties int, kittyID int) [32]byte {
+// 	return GetSlotForArrayItem(slotIndexForKitties, kittyID, 2)
+// }
+pub fn get_kitty_slot(slot_index_for_kitties: u64, kitty_id: u64) -> [u8; 32] {
+    get_slot_for_array_item(slot_index_for_kitties, kitty_id, 2)
+}
+
+// SolType is a Solidity or Vyper type as far as its storage layout is
+// concerned. The sizes of the value types are in bytes, uint64 is Uint(8).
+// The state variables of a contract are found by Layout::variables, in Solidity
+// they are laid out like the members of a struct at slot 0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolType {
+    Uint(usize),
+    Int(usize),
+    Address,
+    Bool,
+    FixedBytes(usize),
+    // bytes and string
+    Bytes,
+    String,
+    // T[n]
+    FixedArray(Box<SolType>, u64),
+    // T[]
+    Array(Box<SolType>),
+    // mapping(K => V), or HashMap[K, V] in Vyper
+    Mapping(Box<SolType>, Box<SolType>),
+    Struct(Vec<SolType>),
+    // DynArray[T, n] in Vyper
+    DynArray(Box<SolType>, u64),
+    // Bytes[n] and String[n] in Vyper
+    BoundedBytes(u64),
+    BoundedString(u64),
+}
+
+// Step goes from a value of a type to a value inside it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Step {
+    // the value of a mapping for the key, given as the big-endian bytes of a
+    // value type or as the content of a bytes or string key
+    Key(Vec<u8>),
+    // the item of an array, or the 32-byte chunk of the data of a bytes or
+    // string value, which Solidity only uses from 32 bytes on
+    Index(u64),
+    // the member of a struct
+    Member(usize),
+}
+
+// Location is where a value is stored: the slot, and for a value type the
+// bytes in it. The offset is counted from the lower-order end of the slot,
+// like in the storage layout output of solc, so a value of size bytes is
+// slot[32 - offset - size..32 - offset].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Location {
+    pub slot: [u8; 32],
+    pub offset: usize,
+    pub size: usize,
+}
+
+// Layout is the set of rules a compiler places the state of a contract in
+// storage by.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Layout {
+    // Solidity packs value types together in a slot and keeps the value of a
+    // mapping at keccak256(key . slot).
+    #[default]
+    Solidity,
+    // Vyper up to 0.2 gives every state variable one slot, whatever its type.
+    // Item i of an array and member i of a struct are at keccak256(slot) + i,
+    // and a Bytes or String keeps its length at keccak256(slot) and its data
+    // in the slots after it. The value of a HashMap is at keccak256(slot . key).
+    VyperLegacy,
+    // Vyper from 0.3 on gives every value whole slots, one after the other, so
+    // a state variable, an item or a member takes as many slots as it needs. A
+    // DynArray, Bytes or String keeps its length in its first slot and its
+    // items or data in the next ones. HashMap values are found like in
+    // VyperLegacy.
+    Vyper,
+}
+
+impl SolType {
+    // size returns the number of bytes of a value type, which can share a slot
+    // with the value types next to it, or None for the other types, which
+    // start a new slot and are not followed by anything in their last slot,
+    // and for value types of a size outside 1..=32.
+    pub fn size(&self) -> Option<usize> {
+        match self {
+            SolType::Uint(size) | SolType::Int(size) | SolType::FixedBytes(size) => {
+                Some(*size).filter(|size| (1..=32).contains(size))
+            }
+            SolType::Address => Some(20),
+            SolType::Bool => Some(1),
+            _ => None,
+        }
+    }
+
+    // slots returns the number of slots taken by a value of the type in the
+    // Solidity layout, as a 256-bit big-endian number.
+    pub fn slots(&self) -> [u8; 32] {
+        Layout::Solidity.slots(self)
+    }
+}
+
+// locate follows the path from the value of type ty stored at slot, in the
+// Solidity layout.
+pub fn locate(ty: &SolType, slot: [u8; 32], path: &[Step]) -> Result<Location, &'static str> {
+    Layout::Solidity.locate(ty, slot, path)
+}
+
+impl Layout {
+    // vyper returns the layout of a contract compiled by the given Vyper
+    // version, as in its "# @version 0.2.4" pragma. A range like "^0.3.7"
+    // stands for its lowest version.
+    pub fn vyper(version: &str) -> Result<Layout, &'static str> {
+        let version = version.split_whitespace().last().unwrap_or("");
+        let mut numbers = version.trim_start_matches(|c: char| !c.is_ascii_digit()).split('.');
+        let mut number = || numbers.next().and_then(|n| n.parse::<u64>().ok()).ok_or("invalid Vyper version");
+        let (major, minor) = (number()?, number()?);
+        Ok(if major == 0 && minor < 3 { Layout::VyperLegacy } else { Layout::Vyper })
+    }
+
+    // slots returns the number of slots taken by a value of the type, as a
+    // 256-bit big-endian number. Nested fixed arrays easily take more than 2^128
+    // slots, the count wraps around 2^256 like the positions computed from it.
+    pub fn slots(self, ty: &SolType) -> [u8; 32] {
+        match (self, ty) {
+            (Layout::Solidity, SolType::FixedArray(item, len)) => match item.size() {
+                Some(size) => slot_index(len.div_ceil((32 / size) as u64)),
+                None => mul(self.slots(item), *len),
+            },
+            (Layout::Vyper, SolType::FixedArray(item, len)) => mul(self.slots(item), *len),
+            (Layout::Vyper, SolType::DynArray(item, len)) => add(mul(self.slots(item), *len), 1),
+            (Layout::Vyper, SolType::BoundedBytes(len) | SolType::BoundedString(len)) => slot_index(1 + len.div_ceil(32)),
+            (Layout::Solidity | Layout::Vyper, SolType::Struct(members)) => self.members(members).1,
+            // a dynamic type keeps its length, or nothing for a mapping, in its
+            // slot and its data somewhere else, and so does everything before
+            // Vyper 0.3
+            _ => slot_index(1),
+        }
+    }
+
+    // variables returns where the state variables of a contract are, given
+    // their types in the order they are declared in.
+    pub fn variables(self, vars: &[SolType]) -> Result<Vec<Location>, &'static str> {
+        if !vars.iter().all(|ty| self.supports(ty)) {
+            return Err(self.unsupported());
+        }
+        let positions = self.members(vars).0;
+        Ok(vars.iter().zip(positions).map(|(ty, (slot, offset))| self.location(ty, slot, offset)).collect())
+    }
+
+    // locate follows the path from the value of type ty stored at slot.
+    pub fn locate(self, ty: &SolType, slot: [u8; 32], path: &[Step]) -> Result<Location, &'static str> {
+        if !self.supports(ty) {
+            return Err(self.unsupported());
+        }
+        let mut ty = ty;
+        let mut location = self.location(ty, slot, 0);
+        for step in path {
+            let (next, slot, offset) = match (ty, step) {
+                (SolType::Mapping(key_type, value), Step::Key(key)) => {
+                    let key = encode_key(key_type, key)?;
+                    let slot = match self {
+                        Layout::Solidity => map_slot(&key, &location.slot),
+                        // Vyper hashes the slot first
+                        Layout::VyperLegacy | Layout::Vyper => keccak256(&[&location.slot, key.as_slice()].concat()),
+                    };
+                    (value.as_ref(), slot, 0)
+                }
+                (SolType::FixedArray(item, len), Step::Index(i)) => {
+                    if i >= len {
+                        return Err("array index out of bounds");
+                    }
+                    let (slot, offset) = self.item_position(item, location.slot, *i);
+                    (item.as_ref(), slot, offset)
+                }
+                // the items of a dynamic array start at keccak256(slot), the length
+                // in storage is not checked
+                (SolType::Array(item), Step::Index(i)) => {
+                    let (slot, offset) = self.item_position(item, keccak256(&location.slot), *i);
+                    (item.as_ref(), slot, offset)
+                }
+                // the items of a DynArray follow its length
+                (SolType::DynArray(item, len), Step::Index(i)) => {
+                    if i >= len {
+                        return Err("array index out of bounds");
+                    }
+                    (item.as_ref(), add(add_word(location.slot, mul(self.slots(item), *i)), 1), 0)
+                }
+                (SolType::Bytes | SolType::String, Step::Index(i)) => {
+                    (&SolType::FixedBytes(32), add(keccak256(&location.slot), *i as u128), 0)
+                }
+                (SolType::BoundedBytes(len) | SolType::BoundedString(len), Step::Index(i)) => {
+                    if *i >= len.div_ceil(32) {
+                        return Err("array index out of bounds");
+                    }
+                    let length = match self {
+                        Layout::VyperLegacy => keccak256(&location.slot),
+                        _ => location.slot,
+                    };
+                    (&SolType::FixedBytes(32), add(length, 1 + *i as u128), 0)
+                }
+                (SolType::Struct(members), Step::Member(m)) => {
+                    let positions = self.members(members).0;
+                    let Some(&(slots, offset)) = positions.get(*m) else { return Err("struct member out of bounds") };
+                    let first = match self {
+                        Layout::VyperLegacy => keccak256(&location.slot),
+                        _ => location.slot,
+                    };
+                    (&members[*m], add_word(first, slots), offset)
+                }
+                _ => return Err("step does not match the type"),
+            };
+            ty = next;
+            location = self.location(ty, slot, offset);
+        }
+        Ok(location)
+    }
+
+    // supports reports whether the type and the types inside it exist in the
+    // language of the layout, with value types of 1 to 32 bytes.
+    fn supports(self, ty: &SolType) -> bool {
+        match ty {
+            SolType::Uint(size) | SolType::Int(size) | SolType::FixedBytes(size) => (1..=32).contains(size),
+            SolType::Bytes | SolType::String => self == Layout::Solidity,
+            SolType::Array(item) => self == Layout::Solidity && self.supports(item),
+            SolType::BoundedBytes(_) | SolType::BoundedString(_) => self != Layout::Solidity,
+            // DynArray came with Vyper 0.3
+            SolType::DynArray(item, _) => self == Layout::Vyper && self.supports(item),
+            SolType::FixedArray(item, _) => self.supports(item),
+            SolType::Mapping(key, value) => self.supports(key) && self.supports(value),
+            SolType::Struct(members) => members.iter().all(|member| self.supports(member)),
+            _ => true,
+        }
+    }
+
+    fn unsupported(self) -> &'static str {
+        match self {
+            Layout::Solidity => "type not supported by Solidity",
+            Layout::VyperLegacy | Layout::Vyper => "type not supported by Vyper",
+        }
+    }
+
+    // location returns where the value of type ty starting at the byte offset
+    // of slot is. Vyper gives every value a whole slot and aligns bytesN to the
+    // left like the ABI encoding, the other value types to the right.
+    fn location(self, ty: &SolType, slot: [u8; 32], offset: usize) -> Location {
+        let size = ty.size().unwrap_or(32);
+        let offset = match (self, ty) {
+            (Layout::Solidity, _) => offset,
+            (_, SolType::FixedBytes(size)) => 32 - size,
+            _ => 0,
+        };
+        Location { slot, offset, size }
+    }
+
+    // members returns the slot and byte offset of each member relative to the
+    // first slot of the struct, and the number of slots of the struct.
+    fn members(self, members: &[SolType]) -> (Vec<([u8; 32], usize)>, [u8; 32]) {
+        if self != Layout::Solidity {
+            let mut positions = Vec::with_capacity(members.len());
+            let mut slot = [0; 32];
+            for member in members {
+                positions.push((slot, 0));
+                slot = add_word(slot, self.slots(member));
+            }
+            return (positions, slot);
+        }
+
+        let mut positions = Vec::with_capacity(members.len());
+        let (mut slot, mut offset) = ([0; 32], 0usize);
+        for member in members {
+            match member.size() {
+                Some(size) => {
+                    if offset + size > 32 {
+                        slot = add(slot, 1);
+                        offset = 0;
+                    }
+                    positions.push((slot, offset));
+                    offset += size;
+                }
+                None => {
+                    if offset > 0 {
+                        slot = add(slot, 1);
+                        offset = 0;
+                    }
+                    positions.push((slot, 0));
+                    slot = add_word(slot, self.slots(member));
+                }
+            }
+        }
+        if offset > 0 {
+            slot = add(slot, 1);
+        }
+        (positions, slot)
+    }
+
+    // item_position returns the slot and byte offset of item i of an array whose
+    // items start at slot. Solidity packs value types as many as fit in a slot.
+    fn item_position(self, item: &SolType, slot: [u8; 32], i: u64) -> ([u8; 32], usize) {
+        match (self, item.size()) {
+            (Layout::Solidity, Some(size)) => {
+                let per_slot = (32 / size) as u64;
+                (add(slot, (i / per_slot) as u128), (i % per_slot) as usize * size)
+            }
+            (Layout::VyperLegacy, _) => (add(keccak256(&slot), i as u128), 0),
+            _ => (add_word(slot, mul(self.slots(item), i)), 0),
+        }
+    }
+}
+
+// encode_key returns the bytes hashed with the slot of a mapping for the key:
+// value types are padded to 32 bytes like in the ABI encoding, the content of
+// bytes and string keys is hashed as it is by Solidity and hashed first by
+// Vyper.
+fn encode_key(key_type: &SolType, key: &[u8]) -> Result<Vec<u8>, &'static str> {
+    let size = match key_type {
+        SolType::Bytes | SolType::String => return Ok(key.to_vec()),
+        SolType::BoundedBytes(len) | SolType::BoundedString(len) if key.len() as u64 > *len => {
+            return Err("mapping key too long")
+        }
+        SolType::BoundedBytes(_) | SolType::BoundedString(_) => return Ok(keccak256(key).to_vec()),
+        _ => key_type.size().ok_or("invalid mapping key type")?,
+    };
+    if key.len() > size {
+        return Err("mapping key too long");
+    }
+    Ok(match key_type {
+        // bytesN is left-aligned
+        SolType::FixedBytes(_) => [key, &[0; 32][key.len()..]].concat(),
+        // a negative int is sign-extended
+        SolType::Int(_) if key.first().is_some_and(|b| b & 0x80 != 0) => [&[0xff; 32][key.len()..], key].concat(),
+        _ => left_pad(key).to_vec(),
+    })
+}
+
+// map_slot returns keccak256(key . slot), where the value of a mapping is.
+fn map_slot(key: &[u8], slot: &[u8; 32]) -> [u8; 32] {
+    keccak256(&[key, slot].concat())
+}
+
+// slot_index returns the slot with the given index, where the state variable
+// declared at that position is stored.
+pub fn slot_index(i: u64) -> [u8; 32] {
+    left_pad(&i.to_be_bytes())
+}
+
+// common.LeftPadBytes(b, 32)
+fn left_pad(b: &[u8]) -> [u8; 32] {
+    let mut padded = [0; 32];
+    padded[32 - b.len()..].copy_from_slice(b);
+    padded
+}
+
+// add returns slot + n modulo 2^256.
+fn add(mut slot: [u8; 32], n: u128) -> [u8; 32] {
+    let mut carry = n;
+    for b in slot.iter_mut().rev() {
+        if carry == 0 {
+            break;
+        }
+        let sum = *b as u128 + (carry & 0xff);
+        *b = sum as u8;
+        carry = (carry >> 8) + (sum >> 8);
+    }
+    slot
+}
+
+// add_word returns slot + n modulo 2^256, for an n that does not fit in a u128.
+fn add_word(mut slot: [u8; 32], n: [u8; 32]) -> [u8; 32] {
+    let mut carry = 0u16;
+    for (b, n) in slot.iter_mut().zip(n).rev() {
+        let sum = *b as u16 + n as u16 + carry;
+        *b = sum as u8;
+        carry = sum >> 8;
+    }
+    slot
+}
+
+// mul returns word * n modulo 2^256.
+fn mul(mut word: [u8; 32], n: u64) -> [u8; 32] {
+    let mut carry = 0u128;
+    for b in word.iter_mut().rev() {
+        let product = *b as u128 * n as u128 + carry;
+        *b = product as u8;
+        carry = product >> 8;
+    }
+    word
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hexutil;
+
+    fn hash(s: &str) -> [u8; 32] {
+        hexutil::decode_fixed(s).unwrap()
+    }
+
+    // struct Kitty {
+    //     uint256 genes;
+    //     uint64 birthTime;
+    //     uint64 cooldownEndBlock;
+    //     uint32 matronId;
+    //     uint32 sireId;
+    //     uint32 siringWithId;
+    //     uint16 cooldownIndex;
+    //     uint16 generation;
+    // }
+    fn kitty() -> SolType {
+        use SolType::Uint;
+        SolType::Struct(vec![Uint(32), Uint(8), Uint(8), Uint(4), Uint(4), Uint(4), Uint(2), Uint(2)])
+    }
+
+    #[test]
+    fn test_get_slot() {
+        // keccak256 of slot 0, slot 1 and slot 6
+        let slot_0 = hash("0x290decd9548b62a8d60345a988386fc84ba6bc95484008f6362f93160ef3e563");
+        let slot_6 = hash("0xf652222313e28459528d920b65115c16c04f3efc82aaedc97be59f3f377c0d3f");
+        assert_eq!(slot_0, get_slot_for_array_item(0, 0, 1));
+        assert_eq!(slot_0, keccak256(&slot_index(0)));
+        assert_eq!(hash("0xb10e2d527612073b26eecdfd717e6a320cf44b4afac2b0732d9fcbe2b7fa0cf6"), keccak256(&slot_index(1)));
+
+        // the genes of kitty 1 are in the third slot after keccak256(6)
+        assert_eq!(hash("0xf652222313e28459528d920b65115c16c04f3efc82aaedc97be59f3f377c0d41"), get_kitty_slot(6, 1));
+        assert_eq!(add(slot_6, 2), get_kitty_slot(6, 1));
+        assert_eq!(add(slot_6, 4), get_slot_for_array_item(6, 2, 2));
+
+        let holder: [u8; 20] = hexutil::decode_fixed("0x467d543e5e4e41aeddf3b6d1997350dd9820a173").unwrap();
+        let slot = get_slot_for_erc20_token_holder(9, &holder);
+        assert_eq!(keccak256(&[left_pad(&holder), slot_index(9)].concat()), slot);
+        assert_eq!(slot, get_slot_for_map_key(&left_pad(&holder), 9));
+
+        // the position wraps around
+        assert_eq!([0; 32], add([0xff; 32], 1));
+        assert_eq!(left_pad(&[0x01, 0x00, 0xfe]), add(left_pad(&[0xff, 0xff]), 0xff));
+    }
+
+    #[test]
+    fn test_locate_mappings() {
+        let holder = [0x46; 20];
+        let spender = [0x77; 20];
+        let balances = SolType::Mapping(Box::new(SolType::Address), Box::new(SolType::Uint(32)));
+        let location = locate(&balances, slot_index(9), &[Step::Key(holder.to_vec())]).unwrap();
+        assert_eq!(Location { slot: get_slot_for_erc20_token_holder(9, &holder), offset: 0, size: 32 }, location);
+
+        // mapping(address => mapping(address => uint256)) allowance
+        let allowance = SolType::Mapping(Box::new(SolType::Address), Box::new(balances.clone()));
+        let location = locate(&allowance, slot_index(10), &[Step::Key(holder.to_vec()), Step::Key(spender.to_vec())]);
+        let inner = get_slot_for_map_key(&left_pad(&holder), 10);
+        assert_eq!(map_slot(&left_pad(&spender), &inner), location.unwrap().slot);
+
+        // keys of the other types
+        let by_name = SolType::Mapping(Box::new(SolType::String), Box::new(SolType::Bool));
+        let location = locate(&by_name, slot_index(2), &[Step::Key(b"abc".to_vec())]).unwrap();
+        assert_eq!(Location { slot: map_slot(b"abc", &slot_index(2)), offset: 0, size: 1 }, location);
+
+        let by_selector = SolType::Mapping(Box::new(SolType::FixedBytes(4)), Box::new(SolType::Address));
+        let location = locate(&by_selector, slot_index(3), &[Step::Key(vec![0xa9, 0x05, 0x9c, 0xbb])]).unwrap();
+        let mut key = [0; 32];
+        key[..4].copy_from_slice(&[0xa9, 0x05, 0x9c, 0xbb]);
+        assert_eq!(map_slot(&key, &slot_index(3)), location.slot);
+
+        let by_delta = SolType::Mapping(Box::new(SolType::Int(2)), Box::new(SolType::Uint(32)));
+        let location = locate(&by_delta, slot_index(4), &[Step::Key(vec![0xff, 0xfe])]).unwrap();
+        let mut key = [0xff; 32];
+        key[31] = 0xfe;
+        assert_eq!(map_slot(&key, &slot_index(4)), location.slot);
+
+        assert_eq!(Err("mapping key too long"), locate(&balances, slot_index(9), &[Step::Key(vec![1; 21])]));
+        let invalid = SolType::Mapping(Box::new(SolType::Array(Box::new(SolType::Bool))), Box::new(SolType::Bool));
+        assert_eq!(Err("invalid mapping key type"), locate(&invalid, slot_index(0), &[Step::Key(vec![])]));
+        assert_eq!(Err("step does not match the type"), locate(&balances, slot_index(9), &[Step::Index(0)]));
+    }
+
+    #[test]
+    fn test_locate_kitties() {
+        // Kitty[] kitties, the sixth state variable of the CryptoKitties contract
+        let kitties = SolType::Array(Box::new(kitty()));
+        assert_eq!(slot_index(2), kitty().slots());
+
+        let genes = locate(&kitties, slot_index(6), &[Step::Index(1), Step::Member(0)]).unwrap();
+        assert_eq!(Location { slot: get_kitty_slot(6, 1), offset: 0, size: 32 }, genes);
+
+        // the other members are packed in the next slot
+        let second = add(get_kitty_slot(6, 1), 1);
+        let expected = [(1, 0, 8), (2, 8, 8), (3, 16, 4), (4, 20, 4), (5, 24, 4), (6, 28, 2), (7, 30, 2)];
+        for (member, offset, size) in expected {
+            let location = locate(&kitties, slot_index(6), &[Step::Index(1), Step::Member(member)]).unwrap();
+            assert_eq!(Location { slot: second, offset, size }, location, "member {}", member);
+        }
+        assert_eq!(Err("struct member out of bounds"), locate(&kitties, slot_index(6), &[Step::Index(1), Step::Member(8)]));
+    }
+
+    #[test]
+    fn test_locate_arrays_and_structs() {
+        use SolType::*;
+        // contract {
+        //     uint128 a;
+        //     uint128 b;
+        //     uint8 c;
+        //     address d;
+        //     uint64[5] e;
+        //     bool f;
+        //     bytes32[2] g;
+        //     string h;
+        //     mapping(uint256 => S) i;
+        // }
+        // struct S {
+        //     uint256 x;
+        //     uint16[] y;
+        // }
+        let s = Struct(vec![Uint(32), Array(Box::new(Uint(2)))]);
+        let contract = Struct(vec![
+            Uint(16),
+            Uint(16),
+            Uint(1),
+            Address,
+            FixedArray(Box::new(Uint(8)), 5),
+            Bool,
+            FixedArray(Box::new(FixedBytes(32)), 2),
+            String,
+            Mapping(Box::new(Uint(32)), Box::new(s)),
+        ]);
+        assert_eq!(slot_index(9), contract.slots());
+
+        let at = |path: &[Step]| locate(&contract, [0; 32], path).unwrap();
+        let var = |i: u64| slot_index(i);
+        assert_eq!(Location { slot: var(0), offset: 0, size: 16 }, at(&[Step::Member(0)]));
+        assert_eq!(Location { slot: var(0), offset: 16, size: 16 }, at(&[Step::Member(1)]));
+        assert_eq!(Location { slot: var(1), offset: 0, size: 1 }, at(&[Step::Member(2)]));
+        assert_eq!(Location { slot: var(1), offset: 1, size: 20 }, at(&[Step::Member(3)]));
+
+        // four uint64 per slot, and the array starts and ends its slots
+        assert_eq!(Location { slot: var(2), offset: 0, size: 32 }, at(&[Step::Member(4)]));
+        assert_eq!(Location { slot: var(2), offset: 24, size: 8 }, at(&[Step::Member(4), Step::Index(3)]));
+        assert_eq!(Location { slot: var(3), offset: 0, size: 8 }, at(&[Step::Member(4), Step::Index(4)]));
+        assert_eq!(Location { slot: var(4), offset: 0, size: 1 }, at(&[Step::Member(5)]));
+        assert_eq!(Location { slot: var(6), offset: 0, size: 32 }, at(&[Step::Member(6), Step::Index(1)]));
+        assert_eq!(Err("array index out of bounds"), locate(&contract, [0; 32], &[Step::Member(6), Step::Index(2)]));
+
+        // the long form of a string keeps its data from keccak256(slot) on
+        assert_eq!(Location { slot: var(7), offset: 0, size: 32 }, at(&[Step::Member(7)]));
+        let chunk = at(&[Step::Member(7), Step::Index(2)]);
+        assert_eq!(Location { slot: add(keccak256(&var(7)), 2), offset: 0, size: 32 }, chunk);
+
+        // i[5].y[17]: sixteen uint16 per slot
+        let location = at(&[Step::Member(8), Step::Key(vec![5]), Step::Member(1), Step::Index(17)]);
+        let y = add(get_slot_for_map_key(&slot_index(5), 8), 1);
+        assert_eq!(Location { slot: add(keccak256(&y), 1), offset: 2, size: 2 }, location);
+    }
+
+    // the state variables of the 3pool contract, which is compiled by Vyper 0.2.4
+    fn three_pool() -> Vec<SolType> {
+        use SolType::*;
+        vec![
+            // coins, balances
+            FixedArray(Box::new(Address), 3),
+            FixedArray(Box::new(Uint(32)), 3),
+            // fee, admin_fee, owner, token
+            Uint(32),
+            Uint(32),
+            Address,
+            Address,
+            // initial_A, future_A, initial_A_time, future_A_time
+            Uint(32),
+            Uint(32),
+            Uint(32),
+            Uint(32),
+            // admin_actions_deadline, transfer_ownership_deadline, future_fee, future_admin_fee
+            Uint(32),
+            Uint(32),
+            Uint(32),
+            Uint(32),
+            // future_owner, is_killed, kill_deadline
+            Address,
+            Bool,
+            Uint(32),
+        ]
+    }
+
+    #[test]
+    fn test_vyper_layout_3pool() {
+        let source = include_str!("../../../curve_3pool/Vyper_contract.vy");
+        let layout = Layout::vyper(source.lines().next().unwrap()).unwrap();
+        assert_eq!(Layout::VyperLegacy, layout);
+
+        // one slot for every state variable
+        let vars = three_pool();
+        let locations = layout.variables(&vars).unwrap();
+        for (i, location) in locations.iter().enumerate() {
+            assert_eq!(slot_index(i as u64), location.slot, "variable {}", i);
+            assert_eq!(0, location.offset, "variable {}", i);
+        }
+        assert_eq!(1, locations[15].size);
+
+        // balances(i) and coins(i) are at keccak256(slot) + i
+        let balances = |i: u64| layout.locate(&vars[1], locations[1].slot, &[Step::Index(i)]).unwrap();
+        assert_eq!(hash("0xb10e2d527612073b26eecdfd717e6a320cf44b4afac2b0732d9fcbe2b7fa0cf6"), balances(0).slot);
+        assert_eq!(hash("0xb10e2d527612073b26eecdfd717e6a320cf44b4afac2b0732d9fcbe2b7fa0cf8"), balances(2).slot);
+        assert_eq!(32, balances(2).size);
+        let coins = |i: u64| layout.locate(&vars[0], locations[0].slot, &[Step::Index(i)]).unwrap();
+        assert_eq!(Location { slot: add(keccak256(&slot_index(0)), 1), offset: 0, size: 20 }, coins(1));
+        assert_eq!(Err("array index out of bounds"), layout.locate(&vars[1], locations[1].slot, &[Step::Index(3)]));
+
+        // admin_balances(i) has no slot: it is ERC20(coins[i]).balanceOf(pool) -
+        // balances[i], so it is proven by coins(i) and balances(i) in the pool and
+        // by the balance of the pool in the storage of the coin
+    }
+
+    #[test]
+    fn test_vyper_layout() {
+        use SolType::*;
+        assert_eq!(Ok(Layout::VyperLegacy), Layout::vyper("0.2.4"));
+        assert_eq!(Ok(Layout::VyperLegacy), Layout::vyper("# @version 0.1.0b17"));
+        assert_eq!(Ok(Layout::Vyper), Layout::vyper("# @version ^0.3.7"));
+        assert_eq!(Ok(Layout::Vyper), Layout::vyper("# pragma version >=0.4.0"));
+        assert_eq!(Err("invalid Vyper version"), Layout::vyper("# @version latest"));
+
+        // the slot comes before the key
+        let holder = [0x46; 20];
+        let balance_of = Mapping(Box::new(Address), Box::new(Uint(32)));
+        let location = Layout::Vyper.locate(&balance_of, slot_index(3), &[Step::Key(holder.to_vec())]).unwrap();
+        assert_eq!(keccak256(&[slot_index(3), left_pad(&holder)].concat()), location.slot);
+        assert_ne!(get_slot_for_erc20_token_holder(3, &holder), location.slot);
+        assert_eq!(location, Layout::VyperLegacy.locate(&balance_of, slot_index(3), &[Step::Key(holder.to_vec())]).unwrap());
+
+        // a Bytes key is hashed
+        let by_name = Mapping(Box::new(BoundedString(8)), Box::new(Bool));
+        let location = Layout::Vyper.locate(&by_name, slot_index(2), &[Step::Key(b"abc".to_vec())]).unwrap();
+        assert_eq!(keccak256(&[slot_index(2), keccak256(b"abc")].concat()), location.slot);
+        assert_eq!(Err("mapping key too long"), Layout::Vyper.locate(&by_name, slot_index(2), &[Step::Key(vec![1; 9])]));
+
+        // struct Point:
+        //     x: int128
+        //     y: int128
+        //
+        // owner: address
+        // points: Point[2]
+        // history: DynArray[uint256, 3]
+        // note: String[40]
+        // selector: bytes4
+        // lookup: HashMap[uint256, Point]
+        let point = Struct(vec![Int(16), Int(16)]);
+        let vars = vec![
+            Address,
+            FixedArray(Box::new(point.clone()), 2),
+            DynArray(Box::new(Uint(32)), 3),
+            BoundedString(40),
+            FixedBytes(4),
+            Mapping(Box::new(Uint(32)), Box::new(point.clone())),
+        ];
+
+        // no packing from Vyper 0.3 on, and the lengths come first
+        let locations = Layout::Vyper.variables(&vars).unwrap();
+        let slots: Vec<[u8; 32]> = locations.iter().map(|location| location.slot).collect();
+        assert_eq!(vec![slot_index(0), slot_index(1), slot_index(5), slot_index(9), slot_index(12), slot_index(13)], slots);
+        assert_eq!(Location { slot: slot_index(0), offset: 0, size: 20 }, locations[0]);
+        // bytesN is left-aligned
+        assert_eq!(Location { slot: slot_index(12), offset: 28, size: 4 }, locations[4]);
+
+        let at = |i: usize, path: &[Step]| Layout::Vyper.locate(&vars[i], locations[i].slot, path);
+        assert_eq!(Ok(Location { slot: slot_index(4), offset: 0, size: 16 }), at(1, &[Step::Index(1), Step::Member(1)]));
+        assert_eq!(Ok(Location { slot: slot_index(8), offset: 0, size: 32 }), at(2, &[Step::Index(2)]));
+        assert_eq!(Err("array index out of bounds"), at(2, &[Step::Index(3)]));
+        assert_eq!(Ok(Location { slot: slot_index(11), offset: 0, size: 32 }), at(3, &[Step::Index(1)]));
+        assert_eq!(Err("array index out of bounds"), at(3, &[Step::Index(2)]));
+        let lookup = keccak256(&[slot_index(13), slot_index(7)].concat());
+        assert_eq!(Ok(Location { slot: add(lookup, 1), offset: 0, size: 16 }), at(5, &[Step::Key(vec![7]), Step::Member(1)]));
+
+        // before Vyper 0.3 every level is hashed
+        let points = FixedArray(Box::new(point), 2);
+        let location = Layout::VyperLegacy.locate(&points, slot_index(1), &[Step::Index(1), Step::Member(1)]).unwrap();
+        assert_eq!(add(keccak256(&add(keccak256(&slot_index(1)), 1)), 1), location.slot);
+        let location = Layout::VyperLegacy.locate(&vars[3], slot_index(2), &[Step::Index(0)]).unwrap();
+        assert_eq!(add(keccak256(&slot_index(2)), 1), location.slot);
+        assert_eq!(Err("type not supported by Vyper"), Layout::VyperLegacy.variables(&vars));
+
+        assert_eq!(Err("type not supported by Solidity"), locate(&vars[2], slot_index(0), &[]));
+    }
+
+    #[test]
+    fn test_locate_invalid_sizes() {
+        use SolType::*;
+        for invalid in [Uint(0), Int(33), FixedBytes(33)] {
+            let array = FixedArray(Box::new(invalid.clone()), 3);
+            let s = Struct(vec![Bool, invalid.clone(), Bool]);
+            assert_eq!(None, invalid.size());
+            assert_eq!(slot_index(3), array.slots());
+            assert_eq!(Err("type not supported by Solidity"), locate(&array, slot_index(0), &[Step::Index(1)]));
+            assert_eq!(Err("type not supported by Solidity"), locate(&s, slot_index(0), &[Step::Member(2)]));
+            assert_eq!(Err("type not supported by Vyper"), Layout::Vyper.locate(&array, slot_index(0), &[Step::Index(1)]));
+            assert_eq!(Err("type not supported by Vyper"), Layout::VyperLegacy.variables(&[Bool, invalid]));
+        }
+        assert_eq!(Err("type not supported by Vyper"), Layout::Vyper.locate(&Array(Box::new(Bool)), slot_index(0), &[]));
+    }
+
+    #[test]
+    fn test_locate_large_arrays() {
+        use SolType::*;
+        // uint256[2**64 - 1][2**64 - 1][2] takes 2^129 - 2^66 + 2 slots, more than a u128 holds
+        let max = u64::MAX;
+        let array = FixedArray(Box::new(FixedArray(Box::new(FixedArray(Box::new(Uint(32)), max)), max)), 2);
+        let slots = hash("0x00000000000000000000000000000001fffffffffffffffc0000000000000002");
+        assert_eq!(slots, array.slots());
+        assert_eq!(slots, Layout::Vyper.slots(&array));
+
+        // the last item is in the last slot of the array, the next variable follows it
+        let last = [Step::Index(1), Step::Index(max - 1), Step::Index(max - 1)];
+        let end = hash("0x00000000000000000000000000000001fffffffffffffffc0000000000000001");
+        assert_eq!(end, locate(&array, slot_index(0), &last).unwrap().slot);
+        assert_eq!(end, Layout::Vyper.locate(&array, slot_index(0), &last).unwrap().slot);
+        assert_eq!(slots, Layout::Solidity.variables(&[array.clone(), Bool]).unwrap()[1].slot);
+
+        assert_eq!(
+            hash("0x0000000000000000fffffffffffffffe00000000000000010000000000000001"),
+            Layout::Vyper.slots(&DynArray(Box::new(array.clone()), 1 << 63))
+        );
+
+        // 2 * (2^64 - 1)^4 slots wrap around 2^256 like the positions in them
+        let larger = FixedArray(Box::new(FixedArray(Box::new(array), max)), max);
+        assert_eq!(hash("0xfffffffffffffff8000000000000000bfffffffffffffff80000000000000002"), larger.slots());
+        let last = [Step::Index(max - 1), Step::Index(max - 1), Step::Index(1), Step::Index(max - 1), Step::Index(max - 1)];
+        assert_eq!(
+            hash("0xfffffffffffffff8000000000000000bfffffffffffffff80000000000000001"),
+            locate(&larger, slot_index(0), &last).unwrap().slot
+        );
+    }
+}
@@ -0,0 +1,154 @@
+use crate::nibbles::Nibble;
+use crate::nodes::{ChildRef, Node};
+use crate::trie::Trie;
+
+// StackTrie computes the root of a trie whose keys arrive in increasing order,
+// like go-ethereum's StackTrie behind DeriveSha. A key can only extend the
+// rightmost path of the trie, so every subtrie to the left of the path of the
+// last key is complete: it is hashed and dropped, and only the rightmost path
+// stays in memory.
+#[derive(Debug, Clone, Default)]
+pub struct StackTrie {
+    trie: Trie,
+    last: Option<Vec<u8>>,
+}
+
+impl StackTrie {
+    pub fn new() -> StackTrie {
+        StackTrie::default()
+    }
+
+    // Reset empties the trie so that it can be reused.
+    pub fn reset(&mut self) {
+        *self = StackTrie::new();
+    }
+
+    // Update inserts the key, which has to be greater than the previous one.
+    // Deleting is not supported, so the value cannot be empty.
+    pub fn update(&mut self, key: &[u8], value: &[u8]) -> Result<(), &'static str> {
+        if value.is_empty() {
+            return Err("empty value");
+        }
+        if self.last.as_deref().is_some_and(|last| key <= last) {
+            return Err("non-ascending key order");
+        }
+
+        let nibbles = Nibble::from_bytes(key.to_vec());
+        // the path of a greater key never leads into a folded subtrie
+        self.trie.put_nibbles(nibbles.clone(), value).expect("the rightmost path is in memory");
+        fold_left(&mut self.trie.root, &nibbles);
+        self.last = Some(key.to_vec());
+        Ok(())
+    }
+
+    // Hash returns the root hash, the same as Trie::hash for the same keys.
+    pub fn hash(&self) -> Vec<u8> {
+        self.trie.hash()
+    }
+}
+
+// fold_left follows the path of the key and replaces every child to the left
+// of it by its hash. Children small enough to be embedded in their parent are
+// kept, they hold no more than their encoding.
+fn fold_left(mut node: &mut Node, nibbles: &[Nibble]) {
+    let mut nibbles = nibbles;
+    loop {
+        match node {
+            Node::Branch(branch) => {
+                let Some((b, rest)) = nibbles.split_first() else { return };
+                for child in branch.branches[..b.to_usize()].iter_mut() {
+                    if let Node::Leaf(_) | Node::Extension(_) | Node::Branch(_) = child {
+                        if let ChildRef::Hash(hash) = child.child_ref() {
+                            *child = Node::Hash(hash);
+                        }
+                    }
+                }
+                node = &mut branch.branches[b.to_usize()];
+                nibbles = rest;
+            }
+            Node::Extension(ext) if nibbles.starts_with(&ext.path) => {
+                nibbles = &nibbles[ext.path.len()..];
+                node = ext.next.as_mut();
+            }
+            _ => return,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::empty::EMPTY_NODE_HASH;
+
+    // loaded counts the nodes held in memory.
+    fn loaded(node: &Node) -> usize {
+        match node {
+            Node::Empty | Node::Hash(_) => 0,
+            Node::Leaf(_) => 1,
+            Node::Extension(ext) => 1 + loaded(&ext.next),
+            Node::Branch(branch) => 1 + branch.branches.iter().map(loaded).sum::<usize>(),
+        }
+    }
+
+    #[test]
+    fn test_stack_trie() {
+        let mut st = StackTrie::new();
+        assert_eq!(EMPTY_NODE_HASH.to_vec(), st.hash());
+
+        let mut tr = Trie::new();
+        for i in 0..1000u32 {
+            let key = i.to_be_bytes();
+            let value = vec![i as u8; 1 + i as usize % 50];
+            st.update(&key, &value).unwrap();
+            tr.put(&key, &value);
+        }
+        assert_eq!(tr.hash(), st.hash());
+
+        // only the rightmost path is in memory
+        assert!(loaded(&st.trie.root) <= 8, "{} nodes in memory", loaded(&st.trie.root));
+
+        st.reset();
+        assert_eq!(EMPTY_NODE_HASH.to_vec(), st.hash());
+    }
+
+    #[test]
+    fn test_stack_trie_prefix_keys() {
+        // a key that is a prefix of the next one ends at a branch node
+        let keys: [&[u8]; 5] = [&[1], &[1, 2], &[1, 2, 3], &[1, 3], &[2]];
+        let mut st = StackTrie::new();
+        let mut tr = Trie::new();
+        for (i, key) in keys.iter().enumerate() {
+            st.update(key, &[i as u8 + 1; 33]).unwrap();
+            tr.put(key, &[i as u8 + 1; 33]);
+            assert_eq!(tr.hash(), st.hash());
+        }
+    }
+
+    #[test]
+    fn test_stack_trie_order() {
+        let mut st = StackTrie::new();
+        st.update(&[1, 2], b"a").unwrap();
+        assert_eq!(Err("non-ascending key order"), st.update(&[1, 2], b"b"));
+        assert_eq!(Err("non-ascending key order"), st.update(&[1], b"b"));
+        assert_eq!(Err("empty value"), st.update(&[1, 3], b""));
+        assert!(st.update(&[1, 3], b"c").is_ok());
+    }
+
+    #[test]
+    fn test_stack_trie_matches_trie() {
+        fn prop(mut pairs: Vec<(Vec<u8>, Vec<u8>)>) -> bool {
+            pairs.retain(|(_, value)| !value.is_empty());
+            pairs.sort();
+            pairs.dedup_by(|a, b| a.0 == b.0);
+
+            let mut st = StackTrie::new();
+            let mut tr = Trie::new();
+            for (key, value) in &pairs {
+                st.update(key, value).unwrap();
+                tr.put(key, value);
+            }
+            st.hash() == tr.hash()
+        }
+        quickcheck::quickcheck(prop as fn(Vec<(Vec<u8>, Vec<u8>)>) -> bool);
+    }
+}
@@ -0,0 +1,412 @@
+use std::fmt;
+
+use rlp::{Decodable, DecoderError, Encodable, Rlp, RlpStream};
+use serde::de::{self, Deserializer};
+use serde::Deserialize;
+
+use crate::crypto::keccak256;
+use crate::empty::EMPTY_NODE_HASH;
+use crate::hexutil;
+use crate::proof::{verify_proof, Proof, ProofError};
+use crate::storage_value::{decode_storage_value, decode_word, ValueError};
+use crate::transaction::{decode_big, decode_fixed};
+
+// EMPTY_CODE_HASH is the code hash of an account without code, keccak256 of
+// the empty string.
+pub const EMPTY_CODE_HASH: [u8; 32] = [
+    0xc5, 0xd2, 0x46, 0x01, 0x86, 0xf7, 0x23, 0x3c, 0x92, 0x7e, 0x7d, 0xb2, 0xdc, 0xc7, 0x03, 0xc0, 0xe5, 0x00, 0xb6,
+    0x53, 0xca, 0x82, 0x27, 0x3b, 0x7b, 0xfa, 0xd8, 0x04, 0x5d, 0x85, 0xa4, 0x70,
+];
+
+// type AccountState struct {
+// 	Nonce       hexutil.Uint64 `json:"nonce"`
+// 	Balance     *hexutil.Big   `json:"balance"`
+// 	StorageHash common.Hash    `json:"storageHash"`
+// 	CodeHash    common.Hash    `json:"codeHash"`
+// }
+//
+// AccountState is the value of an account in the state trie, the RLP list of
+// these four fields.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountState {
+    pub nonce: u64,
+    pub balance: Vec<u8>,
+    pub storage_hash: [u8; 32],
+    pub code_hash: [u8; 32],
+}
+
+impl Default for AccountState {
+    // the state of an account that does not exist
+    fn default() -> AccountState {
+        AccountState { nonce: 0, balance: Vec::new(), storage_hash: EMPTY_NODE_HASH, code_hash: EMPTY_CODE_HASH }
+    }
+}
+
+impl Encodable for AccountState {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        s.begin_list(4);
+        s.append(&self.nonce);
+        s.append(&self.balance);
+        s.append(&&self.storage_hash[..]);
+        s.append(&&self.code_hash[..]);
+    }
+}
+
+impl Decodable for AccountState {
+    fn decode(rlp: &Rlp) -> Result<AccountState, DecoderError> {
+        if rlp.item_count()? != 4 {
+            return Err(DecoderError::RlpIncorrectListLen);
+        }
+        Ok(AccountState {
+            nonce: rlp.val_at(0)?,
+            balance: decode_big(&rlp.at(1)?)?,
+            storage_hash: decode_fixed(&rlp.at(2)?)?,
+            code_hash: decode_fixed(&rlp.at(3)?)?,
+        })
+    }
+}
+
+// type StorageStateResult struct {
+// 	Nonce        hexutil.Uint64  `json:"nonce"`
+// 	Balance      *hexutil.Big    `json:"balance"`
+// 	StorageHash  common.Hash     `json:"storageHash"`
+// 	CodeHash     common.Hash     `json:"codeHash"`
+// 	StorageProof []StorageProof  `json:"storageProof"`
+// 	AccountProof []hexutil.Bytes `json:"accountProof"`
+// }
+//
+// StorageStateResult is the result of eth_getProof (EIP-1186), which comes
+// from an untrusted source until verify has checked it against a state root.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageStateResult {
+    #[serde(deserialize_with = "hexutil::fixed")]
+    pub address: [u8; 20],
+    #[serde(deserialize_with = "hexutil::uint64")]
+    pub nonce: u64,
+    #[serde(deserialize_with = "hexutil::big")]
+    pub balance: Vec<u8>,
+    #[serde(deserialize_with = "hexutil::fixed")]
+    pub storage_hash: [u8; 32],
+    #[serde(deserialize_with = "hexutil::fixed")]
+    pub code_hash: [u8; 32],
+    pub storage_proof: Vec<StorageProof>,
+    #[serde(deserialize_with = "proof_nodes")]
+    pub account_proof: Vec<Vec<u8>>,
+}
+
+// type StorageProof struct {
+// 	Key   HexNibbles      `json:"key"`
+// 	Value HexNibbles      `json:"value"`
+// 	Proof []hexutil.Bytes `json:"proof"`
+// }
+//
+// The key and the value are numbers without leading zeros, like the Go
+// HexNibbles.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct StorageProof {
+    #[serde(deserialize_with = "hex_nibbles")]
+    pub key: Vec<u8>,
+    #[serde(deserialize_with = "hex_nibbles")]
+    pub value: Vec<u8>,
+    #[serde(deserialize_with = "proof_nodes")]
+    pub proof: Vec<Vec<u8>>,
+}
+
+// type EthGetProofResponse struct {
+// 	Result StorageStateResult `json:"result"`
+// }
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct EthGetProofResponse {
+    pub result: StorageStateResult,
+}
+
+// Why an eth_getProof result does not hold against a state root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GetProofError {
+    // the account proof is incomplete or malformed
+    AccountProof(ProofError),
+    // the proven value of the account is not an account
+    InvalidAccount(DecoderError),
+    // the account fields of the result are not the proven ones
+    AccountMismatch,
+    // the storage key has more than 32 bytes, it is not a slot
+    InvalidStorageKey(Vec<u8>),
+    // the proof of the storage key is incomplete or malformed
+    StorageProof([u8; 32], ProofError),
+    // the proven value of the storage key is not the RLP string of a slot
+    InvalidStorageValue([u8; 32], ValueError),
+    // the value of the storage key is not the proven one
+    StorageMismatch([u8; 32]),
+}
+
+impl fmt::Display for GetProofError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GetProofError::AccountProof(err) => write!(f, "invalid account proof: {}", err),
+            GetProofError::InvalidAccount(err) => write!(f, "invalid account rlp: {}", err),
+            GetProofError::AccountMismatch => write!(f, "account state does not match the proof"),
+            GetProofError::InvalidStorageKey(key) => {
+                write!(f, "storage key {} is longer than a slot", hex::encode(key))
+            }
+            GetProofError::StorageProof(key, err) => write!(f, "invalid storage proof for {}: {}", hex::encode(key), err),
+            GetProofError::InvalidStorageValue(key, err) => {
+                write!(f, "invalid storage value for {}: {}", hex::encode(key), err)
+            }
+            GetProofError::StorageMismatch(key) => {
+                write!(f, "storage value for {} does not match the proof", hex::encode(key))
+            }
+        }
+    }
+}
+
+impl std::error::Error for GetProofError {}
+
+impl StorageStateResult {
+    // account returns the account fields of the result.
+    pub fn account(&self) -> AccountState {
+        AccountState {
+            nonce: self.nonce,
+            balance: self.balance.clone(),
+            storage_hash: self.storage_hash,
+            code_hash: self.code_hash,
+        }
+    }
+
+    // verify checks the account proof against the state root, with
+    // keccak256(address) as the key, and then each storage proof against the
+    // proven storage hash, with keccak256 of the 32-byte slot as the key. It
+    // returns the proven account, or None if the account does not exist, in
+    // which case the result has to report the state of an empty account. A
+    // slot that is not in the storage trie has to be reported as zero.
+    pub fn verify(&self, state_root: [u8; 32]) -> Result<Option<AccountState>, GetProofError> {
+        let proof = Proof::from_nodes(self.account_proof.clone());
+        let proven = verify_proof(state_root, &keccak256(&self.address), &proof).map_err(GetProofError::AccountProof)?;
+        let account = proven.map(|value| rlp::decode::<AccountState>(&value)).transpose();
+        let account = account.map_err(GetProofError::InvalidAccount)?;
+        if account.clone().unwrap_or_default() != self.account() {
+            return Err(GetProofError::AccountMismatch);
+        }
+
+        for storage in &self.storage_proof {
+            let key = storage.slot()?;
+            let proof = Proof::from_nodes(storage.proof.clone());
+            let proven = verify_proof(self.storage_hash, &keccak256(&key), &proof)
+                .map_err(|err| GetProofError::StorageProof(key, err))?;
+            let word = match proven {
+                Some(value) => decode_storage_value(&value),
+                None => Ok([0; 32]),
+            };
+            let word = word.map_err(|err| GetProofError::InvalidStorageValue(key, err))?;
+            if storage.word().ok() != Some(word) {
+                return Err(GetProofError::StorageMismatch(key));
+            }
+        }
+        Ok(account)
+    }
+}
+
+impl StorageProof {
+    // slot returns the key left-padded to 32 bytes, the storage slot whose
+    // keccak256 is the key of the storage trie. A key deserialized from JSON
+    // always fits, one set by hand may not.
+    //
+    // key := common.LeftPadBytes(storageProof.Key, 32)
+    pub fn slot(&self) -> Result<[u8; 32], GetProofError> {
+        if self.key.len() > 32 {
+            return Err(GetProofError::InvalidStorageKey(self.key.clone()));
+        }
+        let mut slot = [0; 32];
+        slot[32 - self.key.len()..].copy_from_slice(&self.key);
+        Ok(slot)
+    }
+
+    // word returns the value left-padded to 32 bytes, the content of the slot
+    // to read values from with decode_value.
+    pub fn word(&self) -> Result<[u8; 32], ValueError> {
+        decode_word(&self.value)
+    }
+}
+
+// func (n *HexNibbles) UnmarshalText(input []byte) error {
+// 	input = bytes.TrimPrefix(input, []byte("0x"))
+// 	v, ok := new(big.Int).SetString(string(input), 16)
+// 	if !ok {
+// 		return fmt.Errorf("invalid hex input")
+// 	}
+// 	*n = v.Bytes()
+// 	return nil
+// }
+//
+// Keys and values longer than 32 bytes are rejected, they cannot be slots.
+fn hex_nibbles<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<u8>, D::Error> {
+    let input = String::deserialize(d)?;
+    let input = input.strip_prefix("0x").unwrap_or(&input);
+    let padded = if input.len() % 2 == 1 { format!("0{}", input) } else { input.to_string() };
+    let b = match hex::decode(padded) {
+        Ok(b) if !input.is_empty() => b,
+        _ => return Err(de::Error::custom("invalid hex input")),
+    };
+    let b: Vec<u8> = b.into_iter().skip_while(|b| *b == 0).collect();
+    if b.len() > 32 {
+        return Err(de::Error::custom(hexutil::HexError::Big256Range));
+    }
+    Ok(b)
+}
+
+fn proof_nodes<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<Vec<u8>>, D::Error> {
+    Vec::<String>::deserialize(d)?.iter().map(|s| hexutil::decode(s).map_err(de::Error::custom)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::slot::SolType;
+    use crate::storage_value::{decode_value, SolValue};
+    use crate::trie::Trie;
+
+    fn response(json: &str) -> StorageStateResult {
+        serde_json::from_str::<EthGetProofResponse>(json).unwrap().result
+    }
+
+    #[test]
+    fn test_eip1186_proof() {
+        let result = response(include_str!("../../../eip1186_proof.json"));
+        assert_eq!(hexutil::decode("0xb856af30b938b6f52e5bff365675f358cd52f91b").unwrap(), result.address);
+
+        // get the state root hash from etherscan: https://etherscan.io/block/14900001
+        let state_root = hexutil::decode_fixed("0x024c056bc5db60d71c7908c5fad6050646bd70fd772ff222702d577e2af2e56b").unwrap();
+        let account = result.verify(state_root).unwrap().unwrap();
+        assert_eq!(0x10, account.nonce);
+        assert_eq!(hexutil::decode_big("0x4ef05b2fe9d8c8").unwrap(), account.balance);
+        assert_eq!(EMPTY_NODE_HASH, account.storage_hash);
+        assert_eq!(EMPTY_CODE_HASH, account.code_hash);
+
+        // the account has no storage, so any slot is zero with an empty proof
+        let mut zero = result.clone();
+        zero.storage_proof = vec![StorageProof { key: vec![0x07], value: vec![], proof: vec![] }];
+        assert_eq!(Ok(Some(account)), zero.verify(state_root));
+        zero.storage_proof[0].value = vec![0x01];
+        assert_eq!(Err(GetProofError::StorageMismatch(zero.storage_proof[0].slot().unwrap())), zero.verify(state_root));
+
+        let mut forged = result.clone();
+        forged.balance = hexutil::decode_big("0x4ef05b2fe9d8c9").unwrap();
+        assert_eq!(Err(GetProofError::AccountMismatch), forged.verify(state_root));
+
+        let mut root = state_root;
+        root[0] ^= 1;
+        assert_eq!(Err(GetProofError::AccountProof(ProofError::MissingNode(root))), result.verify(root));
+    }
+
+    #[test]
+    fn test_contract_storage_proof() {
+        // get the state root hash from etherscan: https://etherscan.io/block/11045195
+        let state_root = hexutil::decode_fixed("0x8c571da4c95e212e508c98a50c2640214d23f66e9a591523df6140fd8d113f29").unwrap();
+
+        let slot_0 = response(include_str!("../../../storage_proof_slot_0.json"));
+        let account = slot_0.verify(state_root).unwrap().unwrap();
+        assert_eq!(
+            hexutil::decode_fixed("0x7317ebbe7d6c43dd6944ed0e2c5f79762113cb75fa0bed7124377c0814737fb4").unwrap(),
+            account.storage_hash
+        );
+        assert_eq!([0; 32], slot_0.storage_proof[0].slot().unwrap());
+        let owner = hexutil::decode_fixed("0xde74da73d5102a796559933296c73e7d1c6f37fb").unwrap();
+        let word = slot_0.storage_proof[0].word().unwrap();
+        assert_eq!(Ok(SolValue::Address(owner)), decode_value(&SolType::Address, &word, 0));
+
+        let mut slot_1 = response(include_str!("../../../storage_proof_slot_1.json"));
+        assert_eq!(Ok(Some(account)), slot_1.verify(state_root));
+        let word = slot_1.storage_proof[0].word().unwrap();
+        assert_eq!(Ok(SolValue::Uint(vec![0x02])), decode_value(&SolType::Uint(32), &word, 0));
+
+        slot_1.storage_proof[0].value = vec![0x03];
+        assert_eq!(Err(GetProofError::StorageMismatch(slot_1.storage_proof[0].slot().unwrap())), slot_1.verify(state_root));
+
+        // the proof of slot 1 does not prove slot 0
+        slot_1.storage_proof[0].key = vec![];
+        slot_1.storage_proof[0].value = vec![0x02];
+        assert!(matches!(slot_1.verify(state_root), Err(GetProofError::StorageMismatch(_) | GetProofError::StorageProof(..))));
+    }
+
+    #[test]
+    fn test_absent_account_and_slot() {
+        // a world state with a contract whose slot 0 is set
+        let contract = [0x24; 20];
+        let mut storage = Trie::new();
+        storage.put(&keccak256(&[0; 32]), &rlp::encode(&vec![0x2au8]));
+        // slot 6 holds a value with a byte after its RLP string
+        let mut slot_6 = [0; 32];
+        slot_6[31] = 6;
+        storage.put(&keccak256(&slot_6), &[0x2a, 0x00]);
+        let state = AccountState { nonce: 1, storage_hash: storage.hash().try_into().unwrap(), ..AccountState::default() };
+        let mut world = Trie::new();
+        world.put(&keccak256(&contract), &rlp::encode(&state));
+        world.put(&keccak256(&[0x3a; 20]), &rlp::encode(&AccountState { nonce: 3, ..AccountState::default() }));
+        let state_root: [u8; 32] = world.hash().try_into().unwrap();
+
+        // slot 5 of the contract is zero
+        let mut slot = [0; 32];
+        slot[31] = 5;
+        let result = StorageStateResult {
+            address: contract,
+            nonce: state.nonce,
+            balance: vec![],
+            storage_hash: state.storage_hash,
+            code_hash: state.code_hash,
+            storage_proof: vec![StorageProof {
+                key: vec![5],
+                value: vec![],
+                proof: storage.prove_absence(&keccak256(&slot)).unwrap().serialize(),
+            }],
+            account_proof: world.prove(&keccak256(&contract)).unwrap().serialize(),
+        };
+        assert_eq!(Ok(Some(state.clone())), result.verify(state_root));
+
+        let mut invalid = result.clone();
+        invalid.storage_proof = vec![StorageProof {
+            key: vec![6],
+            value: vec![0x2a],
+            proof: storage.prove(&keccak256(&slot_6)).unwrap().serialize(),
+        }];
+        assert_eq!(
+            Err(GetProofError::InvalidStorageValue(slot_6, ValueError::Rlp(DecoderError::RlpInconsistentLengthAndData))),
+            invalid.verify(state_root)
+        );
+
+        // the account does not exist, its fields are the ones of an empty account
+        let missing = [0x99; 20];
+        let empty = AccountState::default();
+        let absent = StorageStateResult {
+            address: missing,
+            nonce: 0,
+            balance: vec![],
+            storage_hash: empty.storage_hash,
+            code_hash: empty.code_hash,
+            storage_proof: vec![StorageProof { key: vec![], value: vec![], proof: vec![] }],
+            account_proof: world.prove_absence(&keccak256(&missing)).unwrap().serialize(),
+        };
+        assert_eq!(Ok(None), absent.verify(state_root));
+
+        // an existing account cannot be passed off as absent
+        let mut forged = absent.clone();
+        forged.address = contract;
+        forged.account_proof = result.account_proof.clone();
+        assert_eq!(Err(GetProofError::AccountMismatch), forged.verify(state_root));
+        let mut forged = absent.clone();
+        forged.nonce = 1;
+        assert_eq!(Err(GetProofError::AccountMismatch), forged.verify(state_root));
+    }
+
+    #[test]
+    fn test_hex_nibbles() {
+        let proof: StorageProof = serde_json::from_str(r#"{"key": "0x0", "value": "0x00ff", "proof": []}"#).unwrap();
+        assert_eq!(Vec::<u8>::new(), proof.key);
+        assert_eq!(vec![0xff], proof.value);
+        let json = format!(r#"{{"key": "0x{}01", "value": "abc", "proof": []}}"#, "0".repeat(62));
+        let proof: StorageProof = serde_json::from_str(&json).unwrap();
+        assert_eq!(vec![0x01], proof.key);
+        assert_eq!(vec![0x0a, 0xbc], proof.value);
+        assert!(serde_json::from_str::<StorageProof>(r#"{"key": "0x", "value": "0x0", "proof": []}"#).is_err());
+        assert!(serde_json::from_str::<StorageProof>(r#"{"key": "0xzz", "value": "0x0", "proof": []}"#).is_err());
+    }
+}
@@ -0,0 +1,227 @@
+use std::fmt;
+
+use rlp::{DecoderError, Rlp};
+
+use crate::slot::SolType;
+
+// SolValue is a value of a value type read from a storage slot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolValue {
+    // big-endian without leading zeros, like the other numbers of the crate
+    Uint(Vec<u8>),
+    // two's complement, sign-extended to 32 bytes
+    Int([u8; 32]),
+    Address([u8; 20]),
+    Bool(bool),
+    FixedBytes(Vec<u8>),
+}
+
+// Why the content of a slot cannot be read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValueError {
+    // the value in the storage trie is not an RLP string
+    Rlp(DecoderError),
+    // the value has more bytes than a slot
+    TooLong(usize),
+    // the type is not a value type of a valid size
+    InvalidType,
+    // the value does not fit in the slot at the offset
+    OutOfSlot,
+}
+
+impl fmt::Display for ValueError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ValueError::Rlp(err) => write!(f, "invalid storage value rlp: {}", err),
+            ValueError::TooLong(len) => write!(f, "storage value has {} bytes, more than a slot", len),
+            ValueError::InvalidType => write!(f, "not a value type"),
+            ValueError::OutOfSlot => write!(f, "value does not fit in the slot"),
+        }
+    }
+}
+
+impl std::error::Error for ValueError {}
+
+impl From<DecoderError> for ValueError {
+    fn from(err: DecoderError) -> ValueError {
+        ValueError::Rlp(err)
+    }
+}
+
+// decode_storage_value returns the content of a slot from its value in the
+// storage trie, as returned by verify_proof: the RLP string of the content
+// without its leading zeros. A slot that is not in the trie, for which
+// verify_proof returns None, is zero.
+pub fn decode_storage_value(value: &[u8]) -> Result<[u8; 32], ValueError> {
+    let rlp = Rlp::new(value);
+    if rlp.payload_info()?.total() != value.len() {
+        return Err(ValueError::Rlp(DecoderError::RlpInconsistentLengthAndData));
+    }
+    if !rlp.is_data() {
+        return Err(ValueError::Rlp(DecoderError::RlpExpectedToBeData));
+    }
+    decode_word(rlp.data()?)
+}
+
+// decode_word left-pads the content of a slot without its leading zeros, like
+// the value of a StorageProof, to 32 bytes.
+pub fn decode_word(value: &[u8]) -> Result<[u8; 32], ValueError> {
+    if value.len() > 32 {
+        return Err(ValueError::TooLong(value.len()));
+    }
+    let mut word = [0; 32];
+    word[32 - value.len()..].copy_from_slice(value);
+    Ok(word)
+}
+
+// decode_value reads the value of type ty at the byte offset of the slot,
+// counted from its lower-order end like the offset of a Location.
+pub fn decode_value(ty: &SolType, word: &[u8; 32], offset: usize) -> Result<SolValue, ValueError> {
+    let size = match ty.size() {
+        Some(size) if (1..=32).contains(&size) => size,
+        _ => return Err(ValueError::InvalidType),
+    };
+    if offset + size > 32 {
+        return Err(ValueError::OutOfSlot);
+    }
+    let b = &word[32 - offset - size..32 - offset];
+    Ok(match ty {
+        SolType::Uint(_) => SolValue::Uint(b.iter().copied().skip_while(|b| *b == 0).collect()),
+        SolType::Int(_) => {
+            let mut value = if b[0] & 0x80 != 0 { [0xff; 32] } else { [0; 32] };
+            value[32 - size..].copy_from_slice(b);
+            SolValue::Int(value)
+        }
+        SolType::Address => SolValue::Address(b.try_into().expect("an address has 20 bytes")),
+        // like Solidity, any other byte than zero is true
+        SolType::Bool => SolValue::Bool(b[0] != 0),
+        SolType::FixedBytes(_) => SolValue::FixedBytes(b.to_vec()),
+        _ => return Err(ValueError::InvalidType),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::keccak256;
+    use crate::hexutil;
+    use crate::proof::verify_proof;
+    use crate::slot::{get_kitty_slot, get_slot_for_erc20_token_holder, locate, slot_index, Step};
+    use crate::trie::Trie;
+
+    // storage returns a storage trie with the slots set to the contents,
+    // stored like Ethereum does.
+    fn storage(slots: &[([u8; 32], &[u8])]) -> Trie {
+        let mut trie = Trie::new();
+        for (slot, content) in slots {
+            let trimmed: Vec<u8> = content.iter().copied().skip_while(|b| *b == 0).collect();
+            trie.put(&keccak256(slot), &rlp::encode(&trimmed));
+        }
+        trie
+    }
+
+    // proven returns the content of the slot proven by the storage trie.
+    fn proven(trie: &Trie, slot: &[u8; 32]) -> [u8; 32] {
+        let root: [u8; 32] = trie.hash().try_into().unwrap();
+        let proof = trie.prove(&keccak256(slot)).unwrap();
+        let value = verify_proof(root, &keccak256(slot), &proof).unwrap().unwrap();
+        decode_storage_value(&value).unwrap()
+    }
+
+    #[test]
+    fn test_decode_storage_value() {
+        assert_eq!(decode_word(&[0x02]), decode_storage_value(&rlp::encode(&vec![0x02u8])));
+        assert_eq!(Ok([0; 32]), decode_storage_value(&rlp::encode(&Vec::<u8>::new())));
+        assert_eq!(Ok([0xff; 32]), decode_storage_value(&rlp::encode(&vec![0xffu8; 32])));
+
+        let address = hexutil::decode("0xde74da73d5102a796559933296c73e7d1c6f37fb").unwrap();
+        let word = decode_storage_value(&rlp::encode(&address)).unwrap();
+        assert_eq!([0; 12], word[..12]);
+        assert_eq!(address, word[12..]);
+
+        assert_eq!(Err(ValueError::TooLong(33)), decode_storage_value(&rlp::encode(&vec![0xffu8; 33])));
+        assert_eq!(Err(ValueError::TooLong(33)), decode_word(&[1; 33]));
+        assert_eq!(Err(ValueError::Rlp(DecoderError::RlpExpectedToBeData)), decode_storage_value(&[0xc1, 0x01]));
+        assert_eq!(
+            Err(ValueError::Rlp(DecoderError::RlpInconsistentLengthAndData)),
+            decode_storage_value(&[0x81, 0xff, 0x00])
+        );
+        assert!(decode_storage_value(&[]).is_err());
+    }
+
+    #[test]
+    fn test_decode_value() {
+        use SolType::*;
+        // int16 -2, bool true, address, bytes4 and uint32 packed from the lower-order end
+        let mut word = [0; 32];
+        word[30..].copy_from_slice(&[0xff, 0xfe]);
+        word[29] = 0x01;
+        word[9..29].copy_from_slice(&[0x46; 20]);
+        word[5..9].copy_from_slice(&[0xa9, 0x05, 0x9c, 0xbb]);
+        word[1..5].copy_from_slice(&[0x00, 0x00, 0x01, 0x00]);
+
+        let mut minus_two = [0xff; 32];
+        minus_two[31] = 0xfe;
+        assert_eq!(Ok(SolValue::Int(minus_two)), decode_value(&Int(2), &word, 0));
+        assert_eq!(Ok(SolValue::Uint(vec![0xff, 0xfe])), decode_value(&Uint(2), &word, 0));
+        assert_eq!(Ok(SolValue::Bool(true)), decode_value(&Bool, &word, 2));
+        assert_eq!(Ok(SolValue::Address([0x46; 20])), decode_value(&Address, &word, 3));
+        assert_eq!(Ok(SolValue::FixedBytes(vec![0xa9, 0x05, 0x9c, 0xbb])), decode_value(&FixedBytes(4), &word, 23));
+        assert_eq!(Ok(SolValue::Uint(vec![0x01, 0x00])), decode_value(&Uint(4), &word, 27));
+        assert_eq!(Ok(SolValue::Int([0; 32])), decode_value(&Int(1), &word, 31));
+        assert_eq!(Ok(SolValue::Uint(vec![])), decode_value(&Uint(32), &[0; 32], 0));
+
+        assert_eq!(Err(ValueError::OutOfSlot), decode_value(&Address, &word, 13));
+        assert_eq!(Err(ValueError::InvalidType), decode_value(&Uint(0), &word, 0));
+        assert_eq!(Err(ValueError::InvalidType), decode_value(&Uint(33), &word, 0));
+        assert_eq!(Err(ValueError::InvalidType), decode_value(&String, &word, 0));
+    }
+
+    #[test]
+    fn test_erc20_balance() {
+        // the balance of a USDC holder, in the mapping at slot 9
+        let holder: [u8; 20] = hexutil::decode_fixed("0x467d543e5e4e41aeddf3b6d1997350dd9820a173").unwrap();
+        let balance_of = SolType::Mapping(Box::new(SolType::Address), Box::new(SolType::Uint(32)));
+        let location = locate(&balance_of, slot_index(9), &[Step::Key(holder.to_vec())]).unwrap();
+        assert_eq!(get_slot_for_erc20_token_holder(9, &holder), location.slot);
+
+        let balance = decode_word(&[0x01, 0x1f, 0xe0]).unwrap();
+        let trie = storage(&[(location.slot, &balance), (slot_index(0), &[0x77; 20])]);
+        let word = proven(&trie, &location.slot);
+        let value = decode_value(&SolType::Uint(32), &word, location.offset);
+        assert_eq!(Ok(SolValue::Uint(vec![0x01, 0x1f, 0xe0])), value);
+    }
+
+    #[test]
+    fn test_kitty_1() {
+        use SolType::Uint;
+        // Kitty[] kitties at slot 6 of the CryptoKitties contract at block 15289000
+        let kitty = SolType::Struct(vec![Uint(32), Uint(8), Uint(8), Uint(4), Uint(4), Uint(4), Uint(2), Uint(2)]);
+        let kitties = SolType::Array(Box::new(kitty));
+        let genes = hexutil::decode("0x5ad2b318e6724ce4b9290146531884721ad18c63298a5308a55ad6b6b58d").unwrap();
+        let birth_time = 1511417999u64;
+        let second = locate(&kitties, slot_index(6), &[Step::Index(1), Step::Member(1)]).unwrap().slot;
+        let trie = storage(&[
+            // 2013276 kitties
+            (slot_index(6), &[0x1e, 0xb8, 0x5c]),
+            (get_kitty_slot(6, 1), &genes),
+            // the other members of kitty 1 are zero
+            (second, &birth_time.to_be_bytes()),
+        ]);
+
+        let length = locate(&kitties, slot_index(6), &[]).unwrap();
+        let word = proven(&trie, &length.slot);
+        assert_eq!(Ok(SolValue::Uint(vec![0x1e, 0xb8, 0x5c])), decode_value(&Uint(32), &word, 0));
+
+        let member = |m: usize| {
+            let location = locate(&kitties, slot_index(6), &[Step::Index(1), Step::Member(m)]).unwrap();
+            let word = proven(&trie, &location.slot);
+            decode_value(&Uint(location.size), &word, location.offset).unwrap()
+        };
+        assert_eq!(SolValue::Uint(genes), member(0));
+        assert_eq!(SolValue::Uint(vec![0x5a, 0x16, 0x68, 0x8f]), member(1));
+        for m in 2..8 {
+            assert_eq!(SolValue::Uint(vec![]), member(m), "member {}", m);
+        }
+    }
+}
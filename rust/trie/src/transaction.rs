@@ -0,0 +1,792 @@
+use rlp::{Decodable, DecoderError, Encodable, Rlp, RlpStream};
+use serde::de::{self, Deserializer};
+use serde::Deserialize;
+
+use crate::crypto::keccak256;
+use crate::hashing::DerivableList;
+use crate::hexutil;
+
+// Transaction types.
+// const (
+//   LegacyTxType = iota
+//   AccessListTxType
+//   DynamicFeeTxType
+// )
+//
+// BlobTxType (EIP-4844) and SetCodeTxType (EIP-7702) are not in the Go code,
+// whose EncodeIndex writes nothing for them. Any other type is rejected when
+// decoding, so a transaction or receipt of an unknown type cannot be built.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum TxType {
+    #[default]
+    Legacy = 0,
+    AccessList = 1,
+    DynamicFee = 2,
+    Blob = 3,
+    SetCode = 4,
+}
+
+impl TryFrom<u64> for TxType {
+    type Error = DecoderError;
+
+    fn try_from(t: u64) -> Result<TxType, DecoderError> {
+        match t {
+            0 => Ok(TxType::Legacy),
+            1 => Ok(TxType::AccessList),
+            2 => Ok(TxType::DynamicFee),
+            3 => Ok(TxType::Blob),
+            4 => Ok(TxType::SetCode),
+            _ => Err(DecoderError::Custom("transaction type not supported")),
+        }
+    }
+}
+
+impl TxType {
+    // fields returns the number of items in the RLP list of the type.
+    fn fields(self) -> usize {
+        match self {
+            TxType::Legacy => 9,
+            TxType::AccessList => 11,
+            TxType::DynamicFee => 12,
+            TxType::Blob => 14,
+            TxType::SetCode => 13,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for TxType {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<TxType, D::Error> {
+        TxType::try_from(hexutil::uint64(d)?).map_err(de::Error::custom)
+    }
+}
+
+// type Transaction struct {
+//   Type                 uint8           `json:"type,omitempty"`
+//   Nonce                uint64          `json:"nonce"    `
+//   MaxPriorityFeePerGas *big.Int        `json:"maxPriorityFeePerGas"`
+//   MaxFeePerGas         *big.Int        `json:"maxFeePerGas"`
+//   GasPrice             *big.Int        `json:"gasPrice" `
+//   Gas                  uint64          `json:"gas"      `
+//   To                   *common.Address `json:"to"       `
+//   Value                *big.Int        `json:"value"    `
+//   Data                 []byte          `json:"input"    `
+//
+//   // Signature values
+//   V                    *big.Int        `json:"v" `
+//   R                    *big.Int        `json:"r" `
+//   S                    *big.Int        `json:"s" `
+//
+//   ChainID              *big.Int        `json:"chainId,omitempty"`
+//   AccessList           AccessList      `json:"accessList,omitempty"`
+// }
+//
+// The *big.Int fields are big-endian bytes without leading zeros, the way RLP
+// encodes them, and an empty value is zero. The fields a type does not use
+// are ignored when encoding.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Transaction {
+    pub tx_type: TxType,
+    pub nonce: u64,
+    pub max_priority_fee_per_gas: Vec<u8>,
+    pub max_fee_per_gas: Vec<u8>,
+    pub gas_price: Vec<u8>,
+    pub gas: u64,
+    // None means contract creation
+    pub to: Option<[u8; 20]>,
+    pub value: Vec<u8>,
+    pub data: Vec<u8>,
+
+    // Signature values
+    pub v: Vec<u8>,
+    pub r: Vec<u8>,
+    pub s: Vec<u8>,
+
+    pub chain_id: Vec<u8>,
+    pub access_list: AccessList,
+
+    // Blob transaction fields (EIP-4844)
+    pub max_fee_per_blob_gas: Vec<u8>,
+    pub blob_versioned_hashes: Vec<[u8; 32]>,
+
+    // Set code transaction fields (EIP-7702)
+    pub authorization_list: Vec<Authorization>,
+}
+
+// AccessList is an EIP-2930 access list.
+pub type AccessList = Vec<AccessTuple>;
+
+// AccessTuple is the element type of an access list.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct AccessTuple {
+    #[serde(deserialize_with = "hexutil::fixed")]
+    pub address: [u8; 20],
+    #[serde(rename = "storageKeys", deserialize_with = "hexutil::fixed_list")]
+    pub storage_keys: Vec<[u8; 32]>,
+}
+
+// Authorization is an EIP-7702 authorization to set the code of the signer
+// to the code at address. v is the y parity of the signature.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Authorization {
+    #[serde(deserialize_with = "hexutil::big")]
+    pub chain_id: Vec<u8>,
+    #[serde(deserialize_with = "hexutil::fixed")]
+    pub address: [u8; 20],
+    #[serde(deserialize_with = "hexutil::uint64")]
+    pub nonce: u64,
+    #[serde(rename = "yParity", deserialize_with = "hexutil::big")]
+    pub v: Vec<u8>,
+    #[serde(deserialize_with = "hexutil::big")]
+    pub r: Vec<u8>,
+    #[serde(deserialize_with = "hexutil::big")]
+    pub s: Vec<u8>,
+}
+
+impl Encodable for Authorization {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        s.begin_list(6);
+        s.append(&self.chain_id);
+        s.append(&&self.address[..]);
+        s.append(&self.nonce);
+        s.append(&self.v);
+        s.append(&self.r);
+        s.append(&self.s);
+    }
+}
+
+impl Decodable for Authorization {
+    fn decode(rlp: &Rlp) -> Result<Authorization, DecoderError> {
+        if rlp.item_count()? != 6 {
+            return Err(DecoderError::RlpIncorrectListLen);
+        }
+        Ok(Authorization {
+            chain_id: decode_big(&rlp.at(0)?)?,
+            address: decode_fixed(&rlp.at(1)?)?,
+            nonce: rlp.val_at(2)?,
+            v: decode_big(&rlp.at(3)?)?,
+            r: decode_big(&rlp.at(4)?)?,
+            s: decode_big(&rlp.at(5)?)?,
+        })
+    }
+}
+
+// StorageKeys returns the total number of storage keys in the access list.
+pub fn storage_keys(al: &[AccessTuple]) -> usize {
+    al.iter().map(|tuple| tuple.storage_keys.len()).sum()
+}
+
+impl Encodable for AccessTuple {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        s.begin_list(2);
+        s.append(&&self.address[..]);
+        s.begin_list(self.storage_keys.len());
+        for key in &self.storage_keys {
+            s.append(&&key[..]);
+        }
+    }
+}
+
+impl Decodable for AccessTuple {
+    fn decode(rlp: &Rlp) -> Result<AccessTuple, DecoderError> {
+        if rlp.item_count()? != 2 {
+            return Err(DecoderError::RlpIncorrectListLen);
+        }
+        Ok(AccessTuple {
+            address: decode_fixed(&rlp.at(0)?)?,
+            storage_keys: rlp.at(1)?.iter().map(|key| decode_fixed(&key)).collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl Transaction {
+    // payload encodes the RLP list of the fields of the transaction type, the
+    // LegacyTx, AccessListTx and DynamicFeeTx structs of the Go code, and the
+    // BlobTx and SetCodeTx of EIP-4844 and EIP-7702.
+    fn payload(&self) -> Vec<u8> {
+        let mut s = RlpStream::new_list(self.tx_type.fields());
+        match self.tx_type {
+            // type LegacyTx struct {
+            // 	Nonce    uint64          // nonce of sender account
+            // 	GasPrice *big.Int        // wei per gas
+            // 	Gas      uint64          // gas limit
+            // 	To       *common.Address `rlp:"nil"` // nil means contract creation
+            // 	Value    *big.Int        // wei amount
+            // 	Data     []byte          // contract invocation input data
+            // 	V, R, S  *big.Int        // signature values
+            // }
+            TxType::Legacy => {
+                s.append(&self.nonce);
+                s.append(&self.gas_price);
+                s.append(&self.gas);
+                self.append_to(&mut s);
+                s.append(&self.value);
+                s.append(&self.data);
+            }
+            // type AccessListTx struct {
+            // 	ChainID    *big.Int        // destination chain ID
+            // 	Nonce      uint64          // nonce of sender account
+            // 	GasPrice   *big.Int        // wei per gas
+            // 	Gas        uint64          // gas limit
+            // 	To         *common.Address `rlp:"nil"` // nil means contract creation
+            // 	Value      *big.Int        // wei amount
+            // 	Data       []byte          // contract invocation input data
+            // 	AccessList AccessList      // EIP-2930 access list
+            // 	V, R, S    *big.Int        // signature values
+            // }
+            TxType::AccessList => {
+                s.append(&self.chain_id);
+                s.append(&self.nonce);
+                s.append(&self.gas_price);
+                s.append(&self.gas);
+                self.append_to(&mut s);
+                s.append(&self.value);
+                s.append(&self.data);
+                s.append_list(&self.access_list);
+            }
+            // type DynamicFeeTx struct {
+            // 	ChainID              *big.Int
+            // 	Nonce                uint64
+            // 	MaxPriorityFeePerGas *big.Int
+            // 	MaxFeePerGas         *big.Int
+            // 	Gas                  uint64
+            // 	To                   *common.Address `rlp:"nil"` // nil means contract creation
+            // 	Value                *big.Int
+            // 	Data                 []byte
+            // 	AccessList           AccessList
+            //   V, R, S              *big.Int        // signature values
+            // }
+            //
+            // A BlobTx appends maxFeePerBlobGas and the blob versioned hashes to
+            // these fields and a SetCodeTx the authorization list, the To of
+            // both cannot be nil.
+            TxType::DynamicFee | TxType::Blob | TxType::SetCode => {
+                s.append(&self.chain_id);
+                s.append(&self.nonce);
+                s.append(&self.max_priority_fee_per_gas);
+                s.append(&self.max_fee_per_gas);
+                s.append(&self.gas);
+                self.append_to(&mut s);
+                s.append(&self.value);
+                s.append(&self.data);
+                s.append_list(&self.access_list);
+                if self.tx_type == TxType::Blob {
+                    s.append(&self.max_fee_per_blob_gas);
+                    s.begin_list(self.blob_versioned_hashes.len());
+                    for hash in &self.blob_versioned_hashes {
+                        s.append(&&hash[..]);
+                    }
+                }
+                if self.tx_type == TxType::SetCode {
+                    s.append_list(&self.authorization_list);
+                }
+            }
+        }
+        s.append(&self.v);
+        s.append(&self.r);
+        s.append(&self.s);
+        s.out().to_vec()
+    }
+
+    fn append_to(&self, s: &mut RlpStream) {
+        match &self.to {
+            Some(to) => s.append(&&to[..]),
+            None => s.append_empty_data(),
+        };
+    }
+
+    // MarshalBinary returns the canonical encoding of the transaction.
+    // For legacy transactions, it returns the RLP encoding. For EIP-2718 typed
+    // transactions, it returns the type and payload.
+    pub fn marshal_binary(&self) -> Vec<u8> {
+        let payload = self.payload();
+        if self.tx_type == TxType::Legacy {
+            return payload;
+        }
+        let mut buf = Vec::with_capacity(1 + payload.len());
+        buf.push(self.tx_type as u8);
+        buf.extend_from_slice(&payload);
+        buf
+    }
+
+    // UnmarshalBinary decodes the canonical encoding of transactions.
+    // It supports legacy RLP transactions and EIP2718 typed transactions.
+    pub fn unmarshal_binary(b: &[u8]) -> Result<Transaction, DecoderError> {
+        if !b.is_empty() && b[0] > 0x7f {
+            // It's a legacy transaction.
+            return Transaction::from_payload(TxType::Legacy, b);
+        }
+        Transaction::decode_typed(b)
+    }
+
+    // It's an EIP2718 typed transaction envelope.
+    fn decode_typed(b: &[u8]) -> Result<Transaction, DecoderError> {
+        if b.len() <= 1 {
+            return Err(DecoderError::Custom("typed transaction too short"));
+        }
+        match TxType::try_from(b[0] as u64)? {
+            TxType::Legacy => Err(DecoderError::Custom("transaction type not valid in this context")),
+            tx_type => Transaction::from_payload(tx_type, &b[1..]),
+        }
+    }
+
+    // from_payload decodes the RLP list of the fields of the transaction type,
+    // which has to span all of b.
+    fn from_payload(tx_type: TxType, b: &[u8]) -> Result<Transaction, DecoderError> {
+        let rlp = Rlp::new(b);
+        if rlp.payload_info()?.total() != b.len() {
+            return Err(DecoderError::RlpIsTooBig);
+        }
+        if rlp.item_count()? != tx_type.fields() {
+            return Err(DecoderError::RlpIncorrectListLen);
+        }
+
+        let mut items = rlp.iter();
+        let mut next = || items.next().ok_or(DecoderError::RlpIncorrectListLen);
+        let mut tx = Transaction { tx_type, ..Transaction::default() };
+        if tx_type != TxType::Legacy {
+            tx.chain_id = decode_big(&next()?)?;
+        }
+        tx.nonce = next()?.as_val()?;
+        if let TxType::Legacy | TxType::AccessList = tx_type {
+            tx.gas_price = decode_big(&next()?)?;
+        } else {
+            tx.max_priority_fee_per_gas = decode_big(&next()?)?;
+            tx.max_fee_per_gas = decode_big(&next()?)?;
+        }
+        tx.gas = next()?.as_val()?;
+        let to = next()?;
+        tx.to = if to.is_empty() { None } else { Some(decode_fixed(&to)?) };
+        if tx.to.is_none() && matches!(tx_type, TxType::Blob | TxType::SetCode) {
+            return Err(DecoderError::Custom("transaction type cannot create a contract"));
+        }
+        tx.value = decode_big(&next()?)?;
+        tx.data = next()?.as_val()?;
+        if tx_type != TxType::Legacy {
+            tx.access_list = next()?.as_list()?;
+        }
+        if tx_type == TxType::Blob {
+            tx.max_fee_per_blob_gas = decode_big(&next()?)?;
+            tx.blob_versioned_hashes = next()?.iter().map(|hash| decode_fixed(&hash)).collect::<Result<_, _>>()?;
+        }
+        if tx_type == TxType::SetCode {
+            tx.authorization_list = next()?.as_list()?;
+        }
+        tx.v = decode_big(&next()?)?;
+        tx.r = decode_big(&next()?)?;
+        tx.s = decode_big(&next()?)?;
+        Ok(tx)
+    }
+
+    // Hash returns the transaction hash, the keccak of the canonical encoding
+    // (rlpHash for legacy and prefixedRlpHash for typed transactions).
+    pub fn hash(&self) -> [u8; 32] {
+        keccak256(&self.marshal_binary())
+    }
+
+    // func (t Transaction) GetRLP() ([]byte, error) {
+    //   return rlp.EncodeToBytes(t)
+    // }
+    pub fn get_rlp(&self) -> Vec<u8> {
+        rlp::encode(self).to_vec()
+    }
+}
+
+// EncodeRLP implements rlp.Encoder
+//
+// A typed transaction is wrapped in an RLP string, as it is in the transaction
+// list of a block.
+impl Encodable for Transaction {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        if self.tx_type == TxType::Legacy {
+            s.append_raw(&self.payload(), 1);
+        } else {
+            s.append(&self.marshal_binary());
+        }
+    }
+}
+
+// DecodeRLP implements rlp.Decoder
+impl Decodable for Transaction {
+    fn decode(rlp: &Rlp) -> Result<Transaction, DecoderError> {
+        if rlp.is_list() {
+            let raw = rlp.as_raw();
+            return Transaction::from_payload(TxType::Legacy, &raw[..rlp.payload_info()?.total()]);
+        }
+        Transaction::decode_typed(rlp.data()?)
+    }
+}
+
+impl DerivableList for [Transaction] {
+    fn len(&self) -> usize {
+        <[Transaction]>::len(self)
+    }
+
+    fn encode_index(&self, i: usize, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&self[i].marshal_binary());
+    }
+}
+
+// type txJSON struct {
+//   Type                 common.Uint64   `json:"type"`
+//   Nonce                *common.Uint64  `json:"nonce"`
+//   MaxPriorityFeePerGas *common.Big     `json:"maxPriorityFeePerGas,omitempty"`
+//   MaxFeePerGas         *common.Big     `json:"maxFeePerGas,omitempty"`
+//   GasPrice             *common.Big     `json:"gasPrice"`
+//   Gas                  *common.Uint64  `json:"gas"`
+//   To                   *common.Address `json:"to"`
+//   Value                *common.Big     `json:"value"`
+//   Data                 *common.Bytes   `json:"input"`
+//   V                    *common.Big     `json:"v"`
+//   R                    *common.Big     `json:"r"`
+//   S                    *common.Big     `json:"s"`
+//
+//   // Access list transaction fields:
+//   ChainID              *common.Big    `json:"chainId,omitempty"`
+//   AccessList           *AccessList    `json:"accessList,omitempty"`
+// }
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TxJson {
+    #[serde(rename = "type", default)]
+    tx_type: TxType,
+    #[serde(deserialize_with = "hexutil::uint64")]
+    nonce: u64,
+    #[serde(default, deserialize_with = "hexutil::opt_big")]
+    max_priority_fee_per_gas: Option<Vec<u8>>,
+    #[serde(default, deserialize_with = "hexutil::opt_big")]
+    max_fee_per_gas: Option<Vec<u8>>,
+    #[serde(default, deserialize_with = "hexutil::opt_big")]
+    gas_price: Option<Vec<u8>>,
+    #[serde(deserialize_with = "hexutil::uint64")]
+    gas: u64,
+    #[serde(default, deserialize_with = "hexutil::opt_fixed")]
+    to: Option<[u8; 20]>,
+    #[serde(deserialize_with = "hexutil::big")]
+    value: Vec<u8>,
+    #[serde(rename = "input", deserialize_with = "hexutil::bytes")]
+    data: Vec<u8>,
+    #[serde(deserialize_with = "hexutil::big")]
+    v: Vec<u8>,
+    #[serde(deserialize_with = "hexutil::big")]
+    r: Vec<u8>,
+    #[serde(deserialize_with = "hexutil::big")]
+    s: Vec<u8>,
+
+    // Access list transaction fields:
+    #[serde(default, deserialize_with = "hexutil::opt_big")]
+    chain_id: Option<Vec<u8>>,
+    #[serde(default)]
+    access_list: Option<AccessList>,
+
+    // Blob transaction fields:
+    #[serde(default, deserialize_with = "hexutil::opt_big")]
+    max_fee_per_blob_gas: Option<Vec<u8>>,
+    #[serde(default)]
+    blob_versioned_hashes: Option<Hashes>,
+
+    // Set code transaction fields:
+    #[serde(default)]
+    authorization_list: Option<Vec<Authorization>>,
+}
+
+#[derive(Deserialize)]
+struct Hashes(#[serde(deserialize_with = "hexutil::fixed_list")] Vec<[u8; 32]>);
+
+// UnmarshalJSON reads the transaction objects of the JSON-RPC API, only the
+// fields of the transaction type are kept.
+impl<'de> Deserialize<'de> for Transaction {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Transaction, D::Error> {
+        let dec = TxJson::deserialize(d)?;
+        let mut tx = Transaction {
+            nonce: dec.nonce,
+            gas: dec.gas,
+            to: dec.to,
+            value: dec.value,
+            data: dec.data,
+            v: dec.v,
+            r: dec.r,
+            s: dec.s,
+            ..Transaction::default()
+        };
+        match dec.tx_type {
+            TxType::Legacy => {
+                tx.gas_price = dec.gas_price.unwrap_or_default();
+            }
+            TxType::AccessList => {
+                // Access list is optional for now.
+                tx.access_list = dec.access_list.unwrap_or_default();
+                tx.chain_id = dec.chain_id.unwrap_or_default();
+                tx.gas_price = dec.gas_price.unwrap_or_default();
+            }
+            TxType::DynamicFee | TxType::Blob | TxType::SetCode => {
+                // Access list is optional for now.
+                tx.access_list = dec.access_list.unwrap_or_default();
+                tx.chain_id = dec.chain_id.unwrap_or_default();
+                tx.max_priority_fee_per_gas = dec.max_priority_fee_per_gas.unwrap_or_default();
+                tx.max_fee_per_gas = dec.max_fee_per_gas.unwrap_or_default();
+            }
+        }
+        if dec.tx_type == TxType::Blob {
+            tx.max_fee_per_blob_gas = dec.max_fee_per_blob_gas.unwrap_or_default();
+            tx.blob_versioned_hashes = dec.blob_versioned_hashes.map(|hashes| hashes.0).unwrap_or_default();
+        }
+        if dec.tx_type == TxType::SetCode {
+            tx.authorization_list = dec.authorization_list.unwrap_or_default();
+        }
+        tx.tx_type = dec.tx_type;
+        Ok(tx)
+    }
+}
+
+// decode_fixed decodes an RLP string of exactly N bytes, like an address, a
+// hash or a bloom.
+pub(crate) fn decode_fixed<const N: usize>(rlp: &Rlp) -> Result<[u8; N], DecoderError> {
+    rlp.decoder().decode_value(|bytes| match bytes.len() {
+        len if len < N => Err(DecoderError::RlpIsTooShort),
+        len if len > N => Err(DecoderError::RlpIsTooBig),
+        _ => Ok(bytes.try_into().unwrap()),
+    })
+}
+
+// decode_big decodes a *big.Int, which is canonical without leading zeros
+// and at most 256 bits like in the Go rlp package.
+pub(crate) fn decode_big(rlp: &Rlp) -> Result<Vec<u8>, DecoderError> {
+    rlp.decoder().decode_value(|bytes| match bytes {
+        [0, ..] => Err(DecoderError::RlpInvalidIndirection),
+        _ if bytes.len() > 32 => Err(DecoderError::RlpIsTooBig),
+        _ => Ok(bytes.to_vec()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hashing::derive_root;
+    use crate::trie::Trie;
+    use serde_json::Value;
+
+    const FIXTURES: [&str; 15] = [
+        include_str!("../../../transactions_from_block_10467135.json"),
+        include_str!("../../../transactions_from_block_10467136.json"),
+        include_str!("../../../transactions_from_block_10467210.json"),
+        include_str!("../../../transactions_from_block_10467211.json"),
+        include_str!("../../../transactions_from_block_12579043.json"),
+        include_str!("../../../transactions_from_block_13579043.json"),
+        include_str!("../../../transactions_from_block_17166484.json"),
+        include_str!("../../../transactions_from_block_17535192.json"),
+        include_str!("../../../transactions_from_block_17535909.json"),
+        include_str!("../../../transactions_from_block_17535910.json"),
+        include_str!("../../../transactions_from_block_17536090.json"),
+        include_str!("../../../transactions2_from_block_10467210.json"),
+        include_str!("../../../transactions2_from_block_10467211.json"),
+        include_str!("../../../transactions2_from_block_17166484.json"),
+        include_str!("../../../transactions2_from_block_17535910.json"),
+    ];
+
+    const TX_TYPES: [TxType; 5] = [TxType::Legacy, TxType::AccessList, TxType::DynamicFee, TxType::Blob, TxType::SetCode];
+
+    fn transaction(tx_type: TxType) -> Transaction {
+        let legacy_fees = matches!(tx_type, TxType::Legacy | TxType::AccessList);
+        Transaction {
+            tx_type,
+            nonce: 7,
+            max_priority_fee_per_gas: if legacy_fees { vec![] } else { vec![0x3b, 0x9a, 0xca, 0x00] },
+            max_fee_per_gas: if legacy_fees { vec![] } else { vec![0x06, 0x61, 0x8d, 0x9c, 0xc7] },
+            gas_price: if legacy_fees { vec![0x04, 0xa8, 0x17, 0xc8, 0x00] } else { vec![] },
+            gas: 21000,
+            to: Some([0x11; 20]),
+            value: vec![0x01, 0x00],
+            data: vec![0xde, 0xad],
+            v: vec![1],
+            r: vec![0x22; 32],
+            s: vec![0x33; 32],
+            chain_id: if tx_type == TxType::Legacy { vec![] } else { vec![1] },
+            access_list: if tx_type == TxType::Legacy {
+                vec![]
+            } else {
+                vec![AccessTuple { address: [0x44; 20], storage_keys: vec![[0x55; 32], [0x66; 32]] }]
+            },
+            max_fee_per_blob_gas: if tx_type == TxType::Blob { vec![0x77] } else { vec![] },
+            blob_versioned_hashes: if tx_type == TxType::Blob { vec![[0x01; 32], [0x88; 32]] } else { vec![] },
+            authorization_list: if tx_type == TxType::SetCode {
+                vec![Authorization { chain_id: vec![], address: [0x99; 20], nonce: 3, v: vec![1], r: vec![0xaa; 32], s: vec![0xbb; 32] }]
+            } else {
+                vec![]
+            },
+        }
+    }
+
+    #[test]
+    fn test_transaction_encoding() {
+        for tx_type in TX_TYPES {
+            let tx = transaction(tx_type);
+            let b = tx.marshal_binary();
+            if tx_type == TxType::Legacy {
+                assert!(b[0] > 0x7f);
+                assert_eq!(b, tx.get_rlp());
+            } else {
+                // the typed envelope is the type followed by the payload,
+                // wrapped in an RLP string in a list of transactions
+                assert_eq!(tx_type as u8, b[0]);
+                assert_eq!(b, Rlp::new(&tx.get_rlp()).data().unwrap());
+            }
+            assert_eq!(tx, Transaction::unmarshal_binary(&b).unwrap());
+            assert_eq!(tx, rlp::decode::<Transaction>(&tx.get_rlp()).unwrap());
+            assert_eq!(keccak256(&b), tx.hash());
+        }
+        assert_eq!(2, storage_keys(&transaction(TxType::AccessList).access_list));
+
+        let txs: Vec<Transaction> = TX_TYPES.into_iter().map(transaction).collect();
+        assert_eq!(txs, rlp::decode_list::<Transaction>(&rlp::encode_list(&txs)));
+
+        // contract creation
+        let tx = Transaction { to: None, ..transaction(TxType::DynamicFee) };
+        assert_eq!(tx, Transaction::unmarshal_binary(&tx.marshal_binary()).unwrap());
+    }
+
+    #[test]
+    fn test_blob_and_set_code_payloads() {
+        // maxFeePerBlobGas and the versioned hashes follow the access list
+        let b = transaction(TxType::Blob).marshal_binary();
+        let payload = Rlp::new(&b[1..]);
+        assert_eq!(14, payload.item_count().unwrap());
+        assert_eq!(vec![0x77], payload.val_at::<Vec<u8>>(9).unwrap());
+        assert_eq!(2, payload.at(10).unwrap().item_count().unwrap());
+
+        // the authorization list follows the access list
+        let b = transaction(TxType::SetCode).marshal_binary();
+        let payload = Rlp::new(&b[1..]);
+        assert_eq!(13, payload.item_count().unwrap());
+        let auth = payload.at(9).unwrap().at(0).unwrap();
+        assert_eq!(6, auth.item_count().unwrap());
+        assert_eq!(3u64, auth.val_at::<u64>(2).unwrap());
+
+        // neither can create a contract
+        for tx_type in [TxType::Blob, TxType::SetCode] {
+            let tx = Transaction { to: None, ..transaction(tx_type) };
+            assert_eq!(
+                Err(DecoderError::Custom("transaction type cannot create a contract")),
+                Transaction::unmarshal_binary(&tx.marshal_binary())
+            );
+        }
+    }
+
+    #[test]
+    fn test_blob_and_set_code_from_json() {
+        let blob = r#"{
+            "type": "0x3", "chainId": "0x1", "nonce": "0x5", "gas": "0x5208", "gasPrice": "0x1",
+            "maxPriorityFeePerGas": "0x2", "maxFeePerGas": "0x3", "maxFeePerBlobGas": "0x4",
+            "to": "0x1111111111111111111111111111111111111111", "value": "0x0", "input": "0x", "accessList": [],
+            "blobVersionedHashes": ["0x0100000000000000000000000000000000000000000000000000000000000001"],
+            "v": "0x1", "yParity": "0x1", "r": "0x5", "s": "0x6"
+        }"#;
+        let tx: Transaction = serde_json::from_str(blob).unwrap();
+        assert_eq!(TxType::Blob, tx.tx_type);
+        assert_eq!(vec![4], tx.max_fee_per_blob_gas);
+        assert_eq!(1, tx.blob_versioned_hashes.len());
+        assert!(tx.gas_price.is_empty());
+        assert_eq!(tx, Transaction::unmarshal_binary(&tx.marshal_binary()).unwrap());
+
+        let set_code = r#"{
+            "type": "0x4", "chainId": "0x1", "nonce": "0x5", "gas": "0x5208",
+            "maxPriorityFeePerGas": "0x2", "maxFeePerGas": "0x3",
+            "to": "0x1111111111111111111111111111111111111111", "value": "0x0", "input": "0x", "accessList": [],
+            "authorizationList": [{
+                "chainId": "0x0", "address": "0x2222222222222222222222222222222222222222", "nonce": "0x9",
+                "yParity": "0x0", "r": "0x7", "s": "0x8"
+            }],
+            "v": "0x0", "yParity": "0x0", "r": "0x5", "s": "0x6"
+        }"#;
+        let tx: Transaction = serde_json::from_str(set_code).unwrap();
+        assert_eq!(TxType::SetCode, tx.tx_type);
+        assert_eq!(9, tx.authorization_list[0].nonce);
+        assert_eq!([0x22; 20], tx.authorization_list[0].address);
+        assert_eq!(tx, Transaction::unmarshal_binary(&tx.marshal_binary()).unwrap());
+    }
+
+    #[test]
+    fn test_transaction_decode_invalid() {
+        assert_eq!(Err(DecoderError::Custom("typed transaction too short")), Transaction::unmarshal_binary(&[]));
+        assert_eq!(Err(DecoderError::Custom("typed transaction too short")), Transaction::unmarshal_binary(&[1]));
+
+        let mut b = transaction(TxType::DynamicFee).marshal_binary();
+        b[0] = 0x05;
+        assert_eq!(Err(DecoderError::Custom("transaction type not supported")), Transaction::unmarshal_binary(&b));
+        b[0] = 0x00;
+        assert_eq!(
+            Err(DecoderError::Custom("transaction type not valid in this context")),
+            Transaction::unmarshal_binary(&b)
+        );
+
+        // a legacy payload in a dynamic fee envelope
+        let mut b = transaction(TxType::Legacy).marshal_binary();
+        b.insert(0, TxType::DynamicFee as u8);
+        assert_eq!(Err(DecoderError::RlpIncorrectListLen), Transaction::unmarshal_binary(&b));
+
+        // a value with a leading zero is not canonical
+        let tx = Transaction { value: vec![0, 1], ..transaction(TxType::Legacy) };
+        assert_eq!(Err(DecoderError::RlpInvalidIndirection), Transaction::unmarshal_binary(&tx.marshal_binary()));
+
+        let mut b = transaction(TxType::Legacy).marshal_binary();
+        b.push(0);
+        assert_eq!(Err(DecoderError::RlpIsTooBig), Transaction::unmarshal_binary(&b));
+    }
+
+    #[test]
+    fn test_transaction_from_json() {
+        let tx: Transaction = serde_json::from_str(include_str!("../../../transaction.json")).unwrap();
+        assert_eq!(TxType::Legacy, tx.tx_type);
+        assert_eq!(0x144, tx.nonce);
+
+        // gasPrice is null for the dynamic fee transactions of the RPC response
+        let txs: Vec<Transaction> = serde_json::from_str(FIXTURES[14]).unwrap();
+        let tx = &txs[0];
+        assert_eq!(TxType::DynamicFee, tx.tx_type);
+        assert_eq!(0x34, tx.nonce);
+        assert_eq!(vec![0x01, 0x2a, 0x05, 0xf2, 0x00], tx.max_priority_fee_per_gas);
+        assert_eq!(vec![0x06, 0x61, 0x8d, 0x9c, 0xc7], tx.max_fee_per_gas);
+        assert!(tx.gas_price.is_empty());
+        assert_eq!(vec![1], tx.chain_id);
+        assert_eq!(Some(hexutil::decode_fixed("0x3db52ce065f728011ac6732222270b3f2360d919").unwrap()), tx.to);
+
+        let unsupported = r#"{"type": "0x5", "nonce": "0x0", "gas": "0x0", "value": "0x0", "input": "0x", "v": "0x0", "r": "0x0", "s": "0x0"}"#;
+        assert!(serde_json::from_str::<Transaction>(unsupported).is_err());
+    }
+
+    #[test]
+    fn test_transaction_hashes() {
+        // the fixtures written before typed transactions carry the hash
+        for fixture in &FIXTURES[..2] {
+            let values: Vec<Value> = serde_json::from_str(fixture).unwrap();
+            let txs: Vec<Transaction> = serde_json::from_str(fixture).unwrap();
+            for (value, tx) in values.iter().zip(&txs) {
+                assert_eq!(value["hash"].as_str().unwrap(), hexutil::encode(&tx.hash()));
+            }
+        }
+        let value: Value = serde_json::from_str(include_str!("../../../transaction.json")).unwrap();
+        let tx: Transaction = serde_json::from_value(value.clone()).unwrap();
+        assert_eq!(value["hash"].as_str().unwrap(), hexutil::encode(&tx.hash()));
+    }
+
+    #[test]
+    fn test_transaction_fixtures_round_trip() {
+        for fixture in FIXTURES {
+            let txs: Vec<Transaction> = serde_json::from_str(fixture).unwrap();
+
+            let mut tr = Trie::new();
+            for (i, tx) in txs.iter().enumerate() {
+                let b = tx.marshal_binary();
+                assert_eq!(tx, &Transaction::unmarshal_binary(&b).unwrap());
+                assert_eq!(tx, &rlp::decode::<Transaction>(&tx.get_rlp()).unwrap());
+                tr.put(&rlp::encode(&(i as u64)), &b);
+            }
+            assert_eq!(tr.hash(), derive_root(txs.as_slice()));
+        }
+
+        // the transaction root for block 10467135
+        let txs: Vec<Transaction> = serde_json::from_str(FIXTURES[0]).unwrap();
+        assert_eq!("bb345e208bda953c908027a45aa443d6cab6b8d2fd64e83ec52f1008ddeafa58", hex::encode(derive_root(txs.as_slice())));
+    }
+}
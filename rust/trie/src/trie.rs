@@ -0,0 +1,1389 @@
+use std::fmt;
+use std::io;
+use std::sync::Arc;
+
+use rlp::DecoderError;
+
+use crate::branch::BranchNode;
+use crate::crypto::keccak256;
+use crate::db::{MemoryDb, NodeDb};
+use crate::empty::EMPTY_NODE_HASH;
+use crate::extension::ExtensionNode;
+use crate::leaf::LeafNode;
+use crate::nibbles::Nibble;
+use crate::nodes::Node;
+
+// type Trie struct {
+//   root Node
+// }
+//
+// The subtries that are only known by their hash are loaded from db when a key
+// leads into them, see Open and Commit.
+#[derive(Debug, Clone)]
+pub struct Trie {
+    pub(crate) root: Node,
+    db: Arc<dyn NodeDb>,
+}
+
+impl Default for Trie {
+    fn default() -> Self {
+        Trie::new()
+    }
+}
+
+// Entry is a key of the trie and its value.
+pub type Entry = (Vec<u8>, Vec<u8>);
+
+// Why a trie operation could not complete.
+#[derive(Debug)]
+pub enum TrieError {
+    // the key leads into a subtrie that is only known by its hash, and the
+    // database does not have it
+    MissingNode([u8; 32]),
+    // the database failed
+    Db(io::Error),
+    // a node from the database does not decode
+    InvalidNode(DecoderError),
+    // the original key of a hashed key was not recorded, see SecureTrie
+    MissingPreimage([u8; 32]),
+}
+
+impl fmt::Display for TrieError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TrieError::MissingNode(hash) => write!(f, "trie node {} not found", hex::encode(hash)),
+            TrieError::Db(err) => write!(f, "node database: {}", err),
+            TrieError::InvalidNode(err) => write!(f, "invalid trie node: {}", err),
+            TrieError::MissingPreimage(hash) => write!(f, "preimage of {} not found", hex::encode(hash)),
+        }
+    }
+}
+
+impl std::error::Error for TrieError {}
+
+impl From<io::Error> for TrieError {
+    fn from(err: io::Error) -> Self {
+        TrieError::Db(err)
+    }
+}
+
+impl Trie {
+    // func NewTrie() *Trie {
+    //   return &Trie{}
+    // }
+    pub fn new() -> Trie {
+        Trie { root: Node::Empty, db: Arc::new(MemoryDb::new()) }
+    }
+
+    // Open loads the trie with the given root from db. Only the root node is
+    // read, the rest is loaded when a key leads into it.
+    pub fn open(db: Arc<dyn NodeDb>, root: [u8; 32]) -> Result<Trie, TrieError> {
+        if root == EMPTY_NODE_HASH {
+            return Ok(Trie { root: Node::Empty, db });
+        }
+        let root = resolve(db.as_ref(), &root)?;
+        Ok(Trie { root, db })
+    }
+
+    // Commit writes the nodes held in memory to the database, and replaces
+    // them by their hash so that they are loaded again when needed. Reads do
+    // not keep the nodes they load, but updates do: the nodes in memory are the
+    // ones created or changed since the trie was opened or last committed, and
+    // the ones an update loaded without changing them, like the sibling that a
+    // delete merges into its parent. Those are written again as they are.
+    pub fn commit(&mut self) -> Result<[u8; 32], TrieError> {
+        if self.root.is_empty() {
+            return Ok(EMPTY_NODE_HASH);
+        }
+        commit(self.db.as_ref(), &mut self.root, true)?;
+        let Node::Hash(root) = self.root else { unreachable!() };
+        Ok(root)
+    }
+
+    // func (t *Trie) Hash() []byte {
+    //   if IsEmptyNode(t.root) {
+    //     return EmptyNodeHash
+    //   }
+    //   return t.root.Hash()
+    // }
+    pub fn hash(&self) -> Vec<u8> {
+        self.root.hash()
+    }
+
+    // resolve loads the node with the given hash from the database of the trie.
+    pub(crate) fn resolve(&self, hash: &[u8; 32]) -> Result<Node, TrieError> {
+        resolve(self.db.as_ref(), hash)
+    }
+
+    // Get panics when a node on the path of the key cannot be loaded, partial
+    // tries and tries backed by a database are read with TryGet.
+    pub fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.try_get(key).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, TrieError> {
+        // node := t.root
+        // nibbles := FromBytes(key)
+        self.get_at(&self.root, Nibble::from_bytes(key.to_vec()))
+    }
+
+    // get_at is TryGet from the given node down, it starts over below a node
+    // loaded from the database.
+    fn get_at(&self, mut node: &Node, mut nibbles: Vec<Nibble>) -> Result<Option<Vec<u8>>, TrieError> {
+        loop {
+            match node {
+                // if IsEmptyNode(node) {
+                //   return nil, false
+                // }
+                Node::Empty => return Ok(None),
+
+                Node::Hash(hash) => {
+                    let loaded = resolve(self.db.as_ref(), hash)?;
+                    return self.get_at(&loaded, nibbles);
+                }
+
+                // if leaf, ok := node.(*LeafNode); ok {
+                //   matched := PrefixMatchedLen(leaf.Path, nibbles)
+                //   if matched != len(leaf.Path) || matched != len(nibbles) {
+                //     return nil, false
+                //   }
+                //   return leaf.Value, true
+                // }
+                Node::Leaf(leaf) => {
+                    let matched = Nibble::prefix_matched_len(&leaf.path, &nibbles);
+                    if matched != leaf.path.len() || matched != nibbles.len() {
+                        return Ok(None);
+                    }
+                    return Ok(Some(leaf.value.clone()));
+                }
+
+                // if branch, ok := node.(*BranchNode); ok {
+                //   if len(nibbles) == 0 {
+                //     return branch.Value, branch.HasValue()
+                //   }
+                //
+                //   b, remaining := nibbles[0], nibbles[1:]
+                //   nibbles = remaining
+                //   node = branch.Branches[b]
+                //   continue
+                // }
+                Node::Branch(branch) => {
+                    if nibbles.is_empty() {
+                        return Ok(branch.value.clone());
+                    }
+                    let b = nibbles.remove(0);
+                    node = &branch.branches[b.to_usize()];
+                }
+
+                // if ext, ok := node.(*ExtensionNode); ok {
+                //   matched := PrefixMatchedLen(ext.Path, nibbles)
+                //   // E 01020304
+                //   //   010203
+                //   if matched < len(ext.Path) {
+                //     return nil, false
+                //   }
+                //
+                //   nibbles = nibbles[matched:]
+                //   node = ext.Next
+                //   continue
+                // }
+                Node::Extension(ext) => {
+                    let matched = Nibble::prefix_matched_len(&ext.path, &nibbles);
+                    if matched < ext.path.len() {
+                        return Ok(None);
+                    }
+                    nibbles.drain(..matched);
+                    node = &ext.next;
+                }
+            }
+        }
+    }
+
+    // Put adds a key value pair to the trie
+    // In general, the rule is:
+    // - When stopped at an EmptyNode, replace it with a new LeafNode with the remaining path.
+    // - When stopped at a LeafNode, convert it to an ExtensionNode and add a new branch and a new LeafNode.
+    // - When stopped at an ExtensionNode, convert it to another ExtensionNode with shorter path and create a new BranchNode points to the ExtensionNode.
+    //
+    // Put panics when a node on the path of the key cannot be loaded, partial
+    // tries and tries backed by a database are updated with TryPut.
+    pub fn put(&mut self, key: &[u8], value: &[u8]) {
+        self.try_put(key, value).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    // TryPut fails without changing the content of the trie if a node on the
+    // path of the key cannot be loaded.
+    pub fn try_put(&mut self, key: &[u8], value: &[u8]) -> Result<(), TrieError> {
+        // nibbles := FromBytes(key)
+        self.put_nibbles(Nibble::from_bytes(key.to_vec()), value)
+    }
+
+    // put_nibbles is TryPut for a key that is already split into nibbles, it
+    // is used to rebuild the subtrie under a nibble path.
+    pub(crate) fn put_nibbles(&mut self, mut nibbles: Vec<Nibble>, value: &[u8]) -> Result<(), TrieError> {
+        // // need to use pointer, so that I can update root in place without
+        // // keeping trace of the parent node
+        // node := &t.root
+        let mut node = &mut self.root;
+
+        loop {
+            // every node on the path of the key changes
+            node.clear_cache();
+
+            match node {
+                // if IsEmptyNode(*node) {
+                //   leaf := NewLeafNodeFromNibbles(nibbles, value)
+                //   *node = leaf
+                //   return
+                // }
+                Node::Empty => {
+                    *node = Node::Leaf(LeafNode::new_leaf_node_from_nibbles(nibbles, value.to_vec()));
+                    return Ok(());
+                }
+
+                Node::Hash(hash) => *node = resolve(self.db.as_ref(), hash)?,
+
+                Node::Leaf(leaf) => {
+                    // matched := PrefixMatchedLen(leaf.Path, nibbles)
+                    let matched = Nibble::prefix_matched_len(&leaf.path, &nibbles);
+
+                    // // if all matched, update value even if the value are equal
+                    // if matched == len(nibbles) && matched == len(leaf.Path) {
+                    //   newLeaf := NewLeafNodeFromNibbles(leaf.Path, value)
+                    //   *node = newLeaf
+                    //   return
+                    // }
+                    if matched == nibbles.len() && matched == leaf.path.len() {
+                        leaf.value = value.to_vec();
+                        return Ok(());
+                    }
+
+                    // branch := NewBranchNode()
+                    let mut branch = BranchNode::new();
+
+                    // // if matched some nibbles, check if matches either all remaining nibbles
+                    // // or all leaf nibbles
+                    // if matched == len(leaf.Path) {
+                    //   branch.SetValue(leaf.Value)
+                    // }
+                    if matched == leaf.path.len() {
+                        branch.set_value(leaf.value.clone());
+                    }
+
+                    // if matched == len(nibbles) {
+                    //   branch.SetValue(value)
+                    // }
+                    if matched == nibbles.len() {
+                        branch.set_value(value.to_vec());
+                    }
+
+                    // if matched < len(leaf.Path) {
+                    //   // have dismatched
+                    //   // L 01020304 hello
+                    //   // + 010203   world
+                    //
+                    //   // 01020304, 0, 4
+                    //   branchNibble, leafNibbles := leaf.Path[matched], leaf.Path[matched+1:]
+                    //   newLeaf := NewLeafNodeFromNibbles(leafNibbles, leaf.Value) // not :matched+1
+                    //   branch.SetBranch(branchNibble, newLeaf)
+                    // }
+                    if matched < leaf.path.len() {
+                        let (branch_nibble, leaf_nibbles) = (leaf.path[matched], &leaf.path[matched + 1..]);
+                        let new_leaf = LeafNode::new_leaf_node_from_nibbles(leaf_nibbles.to_vec(), leaf.value.clone());
+                        branch.set_branch(branch_nibble, Node::Leaf(new_leaf));
+                    }
+
+                    // if matched < len(nibbles) {
+                    //   // L 01020304 hello
+                    //   // + 010203040 world
+                    //
+                    //   // L 01020304 hello
+                    //   // + 010203040506 world
+                    //   branchNibble, leafNibbles := nibbles[matched], nibbles[matched+1:]
+                    //   newLeaf := NewLeafNodeFromNibbles(leafNibbles, value)
+                    //   branch.SetBranch(branchNibble, newLeaf)
+                    // }
+                    if matched < nibbles.len() {
+                        let (branch_nibble, leaf_nibbles) = (nibbles[matched], &nibbles[matched + 1..]);
+                        let new_leaf = LeafNode::new_leaf_node_from_nibbles(leaf_nibbles.to_vec(), value.to_vec());
+                        branch.set_branch(branch_nibble, Node::Leaf(new_leaf));
+                    }
+
+                    // // if there is matched nibbles, an extension node will be created
+                    // if matched > 0 {
+                    //   // create an extension node for the shared nibbles
+                    //   ext := NewExtensionNode(leaf.Path[:matched], branch)
+                    //   *node = ext
+                    // } else {
+                    //   // when there no matched nibble, there is no need to keep the extension node
+                    //   *node = branch
+                    // }
+                    let branch = Node::Branch(Box::new(branch));
+                    *node = if matched > 0 {
+                        Node::Extension(ExtensionNode::new(leaf.path[..matched].to_vec(), branch))
+                    } else {
+                        branch
+                    };
+
+                    return Ok(());
+                }
+
+                // if branch, ok := (*node).(*BranchNode); ok {
+                //   if len(nibbles) == 0 {
+                //     branch.SetValue(value)
+                //     return
+                //   }
+                //
+                //   b, remaining := nibbles[0], nibbles[1:]
+                //   nibbles = remaining
+                //   node = &branch.Branches[b]
+                //   continue
+                // }
+                Node::Branch(branch) => {
+                    if nibbles.is_empty() {
+                        branch.set_value(value.to_vec());
+                        return Ok(());
+                    }
+
+                    let b = nibbles.remove(0);
+                    node = &mut branch.branches[b.to_usize()];
+                }
+
+                // E 01020304
+                // B 0 hello
+                // L 506 world
+                // + 010203 good
+                // if ext, ok := (*node).(*ExtensionNode); ok {
+                //   matched := PrefixMatchedLen(ext.Path, nibbles)
+                //   if matched < len(ext.Path) {
+                Node::Extension(ext) if Nibble::prefix_matched_len(&ext.path, &nibbles) < ext.path.len() => {
+                    // the extension is split, so it is moved out of the trie
+                    // and replaced once the new branch is built
+                    let Node::Extension(ext) = std::mem::take(node) else { unreachable!() };
+                    let matched = Nibble::prefix_matched_len(&ext.path, &nibbles);
+
+                    // extNibbles, branchNibble, extRemainingnibbles := ext.Path[:matched], ext.Path[matched], ext.Path[matched+1:]
+                    // branch := NewBranchNode()
+                    let ext_nibbles = ext.path[..matched].to_vec();
+                    let branch_nibble = ext.path[matched];
+                    let ext_remaining_nibbles = ext.path[matched + 1..].to_vec();
+                    let next = *ext.next;
+                    let mut branch = BranchNode::new();
+
+                    // if len(extRemainingnibbles) == 0 {
+                    //   // E 0102030
+                    //   // + 010203 good
+                    //   branch.SetBranch(branchNibble, ext.Next)
+                    // } else {
+                    //   // E 01020304
+                    //   // + 010203 good
+                    //   newExt := NewExtensionNode(extRemainingnibbles, ext.Next)
+                    //   branch.SetBranch(branchNibble, newExt)
+                    // }
+                    if ext_remaining_nibbles.is_empty() {
+                        branch.set_branch(branch_nibble, next);
+                    } else {
+                        branch.set_branch(branch_nibble, Node::Extension(ExtensionNode::new(ext_remaining_nibbles, next)));
+                    }
+
+                    // if matched < len(nibbles) {
+                    //   nodeBranchNibble, nodeLeafNibbles := nibbles[matched], nibbles[matched+1:]
+                    //   remainingLeaf := NewLeafNodeFromNibbles(nodeLeafNibbles, value)
+                    //   branch.SetBranch(nodeBranchNibble, remainingLeaf)
+                    // } else if matched == len(nibbles) {
+                    //   branch.SetValue(value)
+                    // } else {
+                    //   panic(fmt.Sprintf("too many matched (%v > %v)", matched, len(nibbles)))
+                    // }
+                    if matched < nibbles.len() {
+                        let (node_branch_nibble, node_leaf_nibbles) = (nibbles[matched], &nibbles[matched + 1..]);
+                        let remaining_leaf = LeafNode::new_leaf_node_from_nibbles(node_leaf_nibbles.to_vec(), value.to_vec());
+                        branch.set_branch(node_branch_nibble, Node::Leaf(remaining_leaf));
+                    } else if matched == nibbles.len() {
+                        branch.set_value(value.to_vec());
+                    } else {
+                        panic!("too many matched ({} > {})", matched, nibbles.len());
+                    }
+
+                    // // if there is no shared extension nibbles any more, then we don't need the extension node
+                    // // any more
+                    // // E 01020304
+                    // // + 1234 good
+                    // if len(extNibbles) == 0 {
+                    //   *node = branch
+                    // } else {
+                    //   // otherwise create a new extension node
+                    //   *node = NewExtensionNode(extNibbles, branch)
+                    // }
+                    let branch = Node::Branch(Box::new(branch));
+                    *node = if ext_nibbles.is_empty() {
+                        branch
+                    } else {
+                        Node::Extension(ExtensionNode::new(ext_nibbles, branch))
+                    };
+                    return Ok(());
+                }
+
+                Node::Extension(ext) => {
+                    let matched = Nibble::prefix_matched_len(&ext.path, &nibbles);
+
+                    // nibbles = nibbles[matched:]
+                    // node = &ext.Next
+                    // continue
+                    nibbles.drain(..matched);
+                    node = ext.next.as_mut();
+                }
+            }
+        }
+    }
+
+    // Delete removes the value stored under key and reports whether there was one.
+    // Nodes left with a single child are collapsed the same way Ethereum does:
+    // - a BranchNode with only a value becomes a LeafNode with an empty path,
+    // - a BranchNode with a single child is merged into that child, prefixed with the child's nibble,
+    // - an ExtensionNode pointing to a LeafNode or another ExtensionNode is merged into it.
+    // So the root hash after deleting a key equals the root hash of a trie built without it.
+    //
+    // Delete panics when a node on the path of the key cannot be loaded,
+    // partial tries and tries backed by a database are updated with TryDelete.
+    pub fn delete(&mut self, key: &[u8]) -> bool {
+        self.try_delete(key).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    // TryDelete fails without changing the content of the trie if a node on
+    // the path of the key cannot be loaded, or the only child left to a branch
+    // node, which is merged into it.
+    pub fn try_delete(&mut self, key: &[u8]) -> Result<bool, TrieError> {
+        let nibbles = Nibble::from_bytes(key.to_vec());
+        delete(self.db.as_ref(), &mut self.root, &nibbles)
+    }
+
+    // Entries returns the key value pairs of the trie in key order. It panics
+    // when a node cannot be loaded, like Get.
+    pub fn entries(&self) -> Vec<Entry> {
+        self.try_entries().unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    // TryEntries loads the subtries that are only known by their hash from
+    // the database on the way.
+    pub fn try_entries(&self) -> Result<Vec<Entry>, TrieError> {
+        let mut entries = Vec::new();
+        self.collect(&self.root, &mut Vec::new(), &mut entries)?;
+        Ok(entries)
+    }
+
+    // collect appends the entries under node, whose path from the root is path.
+    fn collect(&self, node: &Node, path: &mut Vec<Nibble>, entries: &mut Vec<Entry>) -> Result<(), TrieError> {
+        match node {
+            Node::Empty => {}
+            Node::Hash(hash) => self.collect(&self.resolve(hash)?, path, entries)?,
+            Node::Leaf(leaf) => {
+                let key = [path.as_slice(), &leaf.path].concat();
+                entries.push((Nibble::to_bytes(key), leaf.value.clone()));
+            }
+            Node::Extension(ext) => {
+                path.extend_from_slice(&ext.path);
+                self.collect(&ext.next, path, entries)?;
+                path.truncate(path.len() - ext.path.len());
+            }
+            Node::Branch(branch) => {
+                // a key that ends at the branch is smaller than the keys below it
+                if let Some(value) = &branch.value {
+                    entries.push((Nibble::to_bytes(path.clone()), value.clone()));
+                }
+                for (i, child) in branch.branches.iter().enumerate() {
+                    path.push(Nibble(i as u8));
+                    self.collect(child, path, entries)?;
+                    path.pop();
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// resolve loads the node with the given hash from the database.
+pub(crate) fn resolve(db: &dyn NodeDb, hash: &[u8; 32]) -> Result<Node, TrieError> {
+    match db.get(hash)? {
+        Some(encoded) => Node::decode(&encoded).map_err(TrieError::InvalidNode),
+        None => Err(TrieError::MissingNode(*hash)),
+    }
+}
+
+// commit writes the nodes under node bottom-up and replaces the ones that are
+// referenced by hash by Node::Hash. The root is always stored by its hash, even
+// when it is small enough to be embedded.
+fn commit(db: &dyn NodeDb, node: &mut Node, is_root: bool) -> Result<(), TrieError> {
+    match node {
+        Node::Empty | Node::Hash(_) => return Ok(()),
+        Node::Leaf(_) => {}
+        Node::Extension(ext) => commit(db, ext.next.as_mut(), false)?,
+        Node::Branch(branch) => {
+            for child in branch.branches.iter_mut() {
+                commit(db, child, false)?;
+            }
+        }
+    }
+
+    let encoded = node.serialize();
+    if is_root || encoded.len() >= 32 {
+        let hash = keccak256(&encoded);
+        db.put(&hash, &encoded)?;
+        *node = Node::Hash(hash);
+    }
+    Ok(())
+}
+
+fn delete(db: &dyn NodeDb, node: &mut Node, nibbles: &[Nibble]) -> Result<bool, TrieError> {
+    let deleted = match node {
+        Node::Empty => false,
+
+        Node::Hash(hash) => {
+            *node = resolve(db, hash)?;
+            return delete(db, node, nibbles);
+        }
+
+        Node::Leaf(leaf) => {
+            if leaf.path != nibbles {
+                return Ok(false);
+            }
+            *node = Node::Empty;
+            return Ok(true);
+        }
+
+        Node::Branch(branch) => match nibbles.first() {
+            None => {
+                let Some(value) = branch.value.take() else {
+                    return Ok(false);
+                };
+                if let Err(err) = load_last_child(db, branch) {
+                    branch.value = Some(value);
+                    return Err(err);
+                }
+                true
+            }
+            Some(b) => {
+                let i = b.to_usize();
+                if let Node::Hash(hash) = &branch.branches[i] {
+                    branch.branches[i] = resolve(db, hash)?;
+                }
+                match &branch.branches[i] {
+                    // only removing the leaf of the key empties its slot, a
+                    // branch below it still holds other keys afterwards
+                    Node::Leaf(leaf) if leaf.path == nibbles[1..] => {
+                        let leaf = std::mem::take(&mut branch.branches[i]);
+                        if let Err(err) = load_last_child(db, branch) {
+                            branch.branches[i] = leaf;
+                            return Err(err);
+                        }
+                    }
+                    _ => {
+                        if !delete(db, &mut branch.branches[i], &nibbles[1..])? {
+                            return Ok(false);
+                        }
+                    }
+                }
+                true
+            }
+        },
+
+        Node::Extension(ext) => {
+            let matched = Nibble::prefix_matched_len(&ext.path, nibbles);
+            if matched < ext.path.len() {
+                return Ok(false);
+            }
+            delete(db, ext.next.as_mut(), &nibbles[matched..])?
+        }
+    };
+
+    if deleted {
+        node.clear_cache();
+        collapse(node);
+    }
+    Ok(deleted)
+}
+
+// load_last_child loads the child of a branch node left with no value and a
+// single child, which collapsing the branch merges into it. It is only called
+// once the key is known to be removed, so a delete that changes nothing does
+// not read the rest of the trie.
+fn load_last_child(db: &dyn NodeDb, branch: &mut BranchNode) -> Result<(), TrieError> {
+    if branch.has_value() {
+        return Ok(());
+    }
+    let mut left = (0..16).filter(|&i| !branch.branches[i].is_empty());
+    if let (Some(i), None) = (left.next(), left.next()) {
+        if let Node::Hash(hash) = &branch.branches[i] {
+            branch.branches[i] = resolve(db, hash)?;
+        }
+    }
+    Ok(())
+}
+
+// collapse restores the canonical shape of a node whose subtree just lost a key.
+fn collapse(node: &mut Node) {
+    match std::mem::take(node) {
+        Node::Branch(mut branch) => {
+            let children: Vec<usize> = (0..16).filter(|&i| !branch.branches[i].is_empty()).collect();
+
+            *node = match (children.as_slice(), branch.value.take()) {
+                ([], None) => Node::Empty,
+
+                // B [] value
+                // => L [] value
+                ([], Some(value)) => Node::Leaf(LeafNode::new_leaf_node_from_nibbles(vec![], value)),
+
+                // B [i: child]
+                // => child with its path prefixed by i
+                (&[i], None) => {
+                    let child = std::mem::take(&mut branch.branches[i]);
+                    prepend_path(vec![Nibble(i as u8)], child)
+                }
+
+                (_, value) => {
+                    branch.value = value;
+                    Node::Branch(branch)
+                }
+            };
+        }
+
+        // E 0102 -> L 03 hello
+        // => L 010203 hello
+        //
+        // E 0102 -> E 03 -> B
+        // => E 010203 -> B
+        Node::Extension(ext) => {
+            *node = prepend_path(ext.path, *ext.next);
+        }
+
+        other => *node = other,
+    }
+}
+
+// prepend_path puts the given nibbles in front of the node, merging them into
+// its path if it has one.
+fn prepend_path(mut path: Vec<Nibble>, node: Node) -> Node {
+    match node {
+        Node::Empty => Node::Empty,
+        Node::Leaf(leaf) => {
+            path.extend(leaf.path);
+            Node::Leaf(LeafNode::new_leaf_node_from_nibbles(path, leaf.value))
+        }
+        Node::Extension(ext) => {
+            path.extend(ext.path);
+            Node::Extension(ExtensionNode::new(path, *ext.next))
+        }
+        Node::Branch(branch) => Node::Extension(ExtensionNode::new(path, Node::Branch(branch))),
+        // loaded by delete beforehand
+        Node::Hash(hash) => unreachable!("cannot merge a path into the unloaded subtrie {}", hex::encode(hash)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::empty::EMPTY_NODE_HASH;
+
+    // func hexEqual(t *testing.T, hex string, bytes []byte) {
+    //   require.Equal(t, hex, fmt.Sprintf("%x", bytes))
+    // }
+    fn hex_equal(expected_hex: &str, actual: &[u8]) {
+        assert_eq!(expected_hex, hex::encode(actual));
+    }
+
+    fn nibbles(ns: &[u8]) -> Vec<Nibble> {
+        Nibble::from_nibble_bytes(ns.to_vec()).unwrap()
+    }
+
+    // // check basic key-value mapping
+    // func TestGetPut(t *testing.T) {
+    //   t.Run("should get nothing if key does not exist", func(t *testing.T) {
+    //     trie := NewTrie()
+    //     _, found := trie.Get([]byte("notexist"))
+    //     require.Equal(t, false, found)
+    //   })
+    //
+    //   t.Run("should get value if key exist", func(t *testing.T) {
+    //     trie := NewTrie()
+    //     trie.Put([]byte{1, 2, 3, 4}, []byte("hello"))
+    //     val, found := trie.Get([]byte{1, 2, 3, 4})
+    //     require.Equal(t, true, found)
+    //     require.Equal(t, val, []byte("hello"))
+    //   })
+    //
+    //   t.Run("should get updated value", func(t *testing.T) {
+    //     trie := NewTrie()
+    //     trie.Put([]byte{1, 2, 3, 4}, []byte("hello"))
+    //     trie.Put([]byte{1, 2, 3, 4}, []byte("world"))
+    //     val, found := trie.Get([]byte{1, 2, 3, 4})
+    //     require.Equal(t, true, found)
+    //     require.Equal(t, val, []byte("world"))
+    //   })
+    // }
+    #[test]
+    fn test_get_put() {
+        // should get nothing if key does not exist
+        let trie = Trie::new();
+        assert_eq!(trie.get(b"notexist"), None);
+
+        // should get value if key exist
+        let mut trie = Trie::new();
+        trie.put(&[1, 2, 3, 4], b"hello");
+        assert_eq!(trie.get(&[1, 2, 3, 4]), Some(b"hello".to_vec()));
+
+        // should get updated value
+        let mut trie = Trie::new();
+        trie.put(&[1, 2, 3, 4], b"hello");
+        trie.put(&[1, 2, 3, 4], b"world");
+        assert_eq!(trie.get(&[1, 2, 3, 4]), Some(b"world".to_vec()));
+    }
+
+    // // verify data integrity
+    // func TestDataIntegrity(t *testing.T) {
+    //   t.Run("should get a different hash if a new key-value pair was added or updated", func(t *testing.T) {
+    //     trie := NewTrie()
+    //     hash0 := trie.Hash()
+    //
+    //     trie.Put([]byte{1, 2, 3, 4}, []byte("hello"))
+    //     hash1 := trie.Hash()
+    //
+    //     trie.Put([]byte{1, 2}, []byte("world"))
+    //     hash2 := trie.Hash()
+    //
+    //     trie.Put([]byte{1, 2}, []byte("trie"))
+    //     hash3 := trie.Hash()
+    //
+    //     require.NotEqual(t, hash0, hash1)
+    //     require.NotEqual(t, hash1, hash2)
+    //     require.NotEqual(t, hash2, hash3)
+    //   })
+    //
+    //   t.Run("should get the same hash if two tries have the identicial key-value pairs", func(t *testing.T) {
+    //     trie1 := NewTrie()
+    //     trie1.Put([]byte{1, 2, 3, 4}, []byte("hello"))
+    //     trie1.Put([]byte{1, 2}, []byte("world"))
+    //
+    //     trie2 := NewTrie()
+    //     trie2.Put([]byte{1, 2, 3, 4}, []byte("hello"))
+    //     trie2.Put([]byte{1, 2}, []byte("world"))
+    //
+    //     require.Equal(t, trie1.Hash(), trie2.Hash())
+    //   })
+    // }
+    #[test]
+    fn test_data_integrity() {
+        let mut trie = Trie::new();
+
+        // should get a different hash if a new key-value pair was added or updated
+        let hash0 = trie.hash();
+        trie.put(&[1, 2, 3, 4], b"hello");
+        let hash1 = trie.hash();
+        trie.put(&[1, 2], b"world");
+        let hash2 = trie.hash();
+        trie.put(&[1, 2], b"trie");
+        let hash3 = trie.hash();
+
+        assert_ne!(hash0, hash1);
+        assert_ne!(hash1, hash2);
+        assert_ne!(hash2, hash3);
+
+        // should get the same hash if two tries have the identicial key-value pairs
+        let mut trie1 = Trie::new();
+        trie1.put(&[1, 2, 3, 4], b"hello");
+        trie1.put(&[1, 2], b"world");
+
+        let mut trie2 = Trie::new();
+        trie2.put(&[1, 2, 3, 4], b"hello");
+        trie2.put(&[1, 2], b"world");
+
+        assert_eq!(trie1.hash(), trie2.hash());
+    }
+
+    // func TestPut2Pairs(t *testing.T) {
+    //   trie := NewTrie()
+    //   trie.Put([]byte{1, 2, 3, 4}, []byte("verb"))
+    //   trie.Put([]byte{1, 2, 3, 4, 5, 6}, []byte("coin"))
+    //
+    //   verb, ok := trie.Get([]byte{1, 2, 3, 4})
+    //   require.True(t, ok)
+    //   require.Equal(t, []byte("verb"), verb)
+    //
+    //   coin, ok := trie.Get([]byte{1, 2, 3, 4, 5, 6})
+    //   require.True(t, ok)
+    //   require.Equal(t, []byte("coin"), coin)
+    //
+    //   fmt.Printf("%T\n", trie.root)
+    //   ext, ok := trie.root.(*ExtensionNode)
+    //   require.True(t, ok)
+    //   branch, ok := ext.Next.(*BranchNode)
+    //   require.True(t, ok)
+    //   leaf, ok := branch.Branches[0].(*LeafNode)
+    //   require.True(t, ok)
+    //
+    //   hexEqual(t, "c37ec985b7a88c2c62beb268750efe657c36a585beb435eb9f43b839846682ce", leaf.Hash())
+    //   hexEqual(t, "ddc882350684636f696e8080808080808080808080808080808476657262", branch.Serialize())
+    //   hexEqual(t, "d757709f08f7a81da64a969200e59ff7e6cd6b06674c3f668ce151e84298aa79", branch.Hash())
+    //   hexEqual(t, "64d67c5318a714d08de6958c0e63a05522642f3f1087c6fd68a97837f203d359", ext.Hash())
+    // }
+    #[test]
+    fn test_put_2_pairs() {
+        let mut trie = Trie::new();
+        trie.put(&[1, 2, 3, 4], b"verb");
+        trie.put(&[1, 2, 3, 4, 5, 6], b"coin");
+
+        assert_eq!(trie.get(&[1, 2, 3, 4]), Some(b"verb".to_vec()));
+        assert_eq!(trie.get(&[1, 2, 3, 4, 5, 6]), Some(b"coin".to_vec()));
+
+        let Node::Extension(ext) = &trie.root else { panic!("root is not an extension node") };
+        let Node::Branch(branch) = ext.next.as_ref() else { panic!("ext.next is not a branch node") };
+        let Node::Leaf(leaf) = &branch.branches[0] else { panic!("branch[0] is not a leaf node") };
+
+        hex_equal("c37ec985b7a88c2c62beb268750efe657c36a585beb435eb9f43b839846682ce", &leaf.hash());
+        hex_equal("ddc882350684636f696e8080808080808080808080808080808476657262", &branch.serialize());
+        hex_equal("d757709f08f7a81da64a969200e59ff7e6cd6b06674c3f668ce151e84298aa79", &branch.hash());
+        hex_equal("64d67c5318a714d08de6958c0e63a05522642f3f1087c6fd68a97837f203d359", &ext.hash());
+    }
+
+    // func TestPut(t *testing.T) {
+    //   trie := NewTrie()
+    //   require.Equal(t, EmptyNodeHash, trie.Hash())
+    //   trie.Put([]byte{1, 2, 3, 4}, []byte("hello"))
+    //   ns := NewLeafNodeFromBytes([]byte{1, 2, 3, 4}, []byte("hello"))
+    //   require.Equal(t, ns.Hash(), trie.Hash())
+    // }
+    #[test]
+    fn test_put() {
+        let mut trie = Trie::new();
+        assert_eq!(EMPTY_NODE_HASH.to_vec(), trie.hash());
+
+        trie.put(&[1, 2, 3, 4], b"hello");
+        let ns = LeafNode::new_from_bytes(&[1, 2, 3, 4], b"hello");
+        assert_eq!(ns.hash(), trie.hash());
+    }
+
+    // func TestPutLeafShorter(t *testing.T) {
+    //   trie := NewTrie()
+    //   trie.Put([]byte{1, 2, 3, 4}, []byte("hello"))
+    //   trie.Put([]byte{1, 2, 3}, []byte("world"))
+    //
+    //   leaf := NewLeafNodeFromNibbles([]Nibble{4}, []byte("hello"))
+    //
+    //   branch := NewBranchNode()
+    //   branch.SetBranch(Nibble(0), leaf)
+    //   branch.SetValue([]byte("world"))
+    //
+    //   ext := NewExtensionNode([]Nibble{0, 1, 0, 2, 0, 3}, branch)
+    //
+    //   require.Equal(t, ext.Hash(), trie.Hash())
+    // }
+    #[test]
+    fn test_put_leaf_shorter() {
+        let mut trie = Trie::new();
+        trie.put(&[1, 2, 3, 4], b"hello");
+        trie.put(&[1, 2, 3], b"world");
+
+        let leaf = LeafNode::new_leaf_node_from_nibbles(nibbles(&[4]), b"hello".to_vec());
+
+        let mut branch = BranchNode::new();
+        branch.set_branch(Nibble(0), Node::Leaf(leaf));
+        branch.set_value(b"world".to_vec());
+
+        let ext = ExtensionNode::new(nibbles(&[0, 1, 0, 2, 0, 3]), Node::Branch(Box::new(branch)));
+
+        assert_eq!(ext.hash(), trie.hash());
+    }
+
+    // func TestPutLeafAllMatched(t *testing.T) {
+    //   trie := NewTrie()
+    //   trie.Put([]byte{1, 2, 3, 4}, []byte("hello"))
+    //   trie.Put([]byte{1, 2, 3, 4}, []byte("world"))
+    //
+    //   ns := NewLeafNodeFromBytes([]byte{1, 2, 3, 4}, []byte("world"))
+    //   require.Equal(t, ns.Hash(), trie.Hash())
+    // }
+    #[test]
+    fn test_put_leaf_all_matched() {
+        let mut trie = Trie::new();
+        trie.put(&[1, 2, 3, 4], b"hello");
+        trie.put(&[1, 2, 3, 4], b"world");
+
+        let ns = LeafNode::new_from_bytes(&[1, 2, 3, 4], b"world");
+        assert_eq!(ns.hash(), trie.hash());
+    }
+
+    // func TestPutLeafMore(t *testing.T) {
+    //   trie := NewTrie()
+    //   trie.Put([]byte{1, 2, 3, 4}, []byte("hello"))
+    //   trie.Put([]byte{1, 2, 3, 4, 5, 6}, []byte("world"))
+    //
+    //   leaf := NewLeafNodeFromNibbles([]Nibble{5, 0, 6}, []byte("world"))
+    //
+    //   branch := NewBranchNode()
+    //   branch.SetValue([]byte("hello"))
+    //   branch.SetBranch(Nibble(0), leaf)
+    //
+    //   ext := NewExtensionNode([]Nibble{0, 1, 0, 2, 0, 3, 0, 4}, branch)
+    //
+    //   require.Equal(t, ext.Hash(), trie.Hash())
+    // }
+    #[test]
+    fn test_put_leaf_more() {
+        let mut trie = Trie::new();
+        trie.put(&[1, 2, 3, 4], b"hello");
+        trie.put(&[1, 2, 3, 4, 5, 6], b"world");
+
+        let leaf = LeafNode::new_leaf_node_from_nibbles(nibbles(&[5, 0, 6]), b"world".to_vec());
+
+        let mut branch = BranchNode::new();
+        branch.set_value(b"hello".to_vec());
+        branch.set_branch(Nibble(0), Node::Leaf(leaf));
+
+        let ext = ExtensionNode::new(nibbles(&[0, 1, 0, 2, 0, 3, 0, 4]), Node::Branch(Box::new(branch)));
+
+        assert_eq!(ext.hash(), trie.hash());
+    }
+
+    // func TestPutOrder(t *testing.T) {
+    //   trie1, trie2 := NewTrie(), NewTrie()
+    //
+    //   trie1.Put([]byte{1, 2, 3, 4, 5, 6}, []byte("world"))
+    //   trie1.Put([]byte{1, 2, 3, 4}, []byte("hello"))
+    //
+    //   trie2.Put([]byte{1, 2, 3, 4}, []byte("hello"))
+    //   trie2.Put([]byte{1, 2, 3, 4, 5, 6}, []byte("world"))
+    //
+    //   require.Equal(t, trie1.Hash(), trie2.Hash())
+    // }
+    #[test]
+    fn test_put_order() {
+        let mut trie1 = Trie::new();
+        let mut trie2 = Trie::new();
+
+        trie1.put(&[1, 2, 3, 4, 5, 6], b"world");
+        trie1.put(&[1, 2, 3, 4], b"hello");
+
+        trie2.put(&[1, 2, 3, 4], b"hello");
+        trie2.put(&[1, 2, 3, 4, 5, 6], b"world");
+
+        assert_eq!(trie1.hash(), trie2.hash());
+    }
+
+    // Before put:
+    //
+    //               ┌───────────────────────────┐
+    //               │  Extension Node           │
+    //               │  Path: [0, 1, 0, 2, 0, 3] │
+    //               └────────────┬──────────────┘
+    //                            │
+    //    ┌───────────────────────┴──────────────────┐
+    //    │                   Branch Node            │
+    //    │   [0]         ...          [5]           │
+    //    └────┼────────────────────────┼────────────┘
+    //         │                        │
+    //         │                        │
+    //         │                        │
+    //         │                        │
+    //   ┌───────┴──────────┐   ┌─────────┴─────────┐
+    //   │  Leaf Node       │   │  Leaf Node        │
+    //   │  Path: [4]       │   │  Path: [0]        │
+    //   │  Value: "hello1" │   │  Value: "hello2"  │
+    //   └──────────────────┘   └───────────────────┘
+    //
+    // After put([]byte{[1, 2, 3]}, "world"):
+    //               ┌───────────────────────────┐
+    //               │  Extension Node           │
+    //               │  Path: [0, 1, 0, 2, 0, 3] │
+    //               └────────────┬──────────────┘
+    //                            │
+    //    ┌───────────────────────┴────────────────────────┐
+    //    │                   Branch Node                  │
+    //    │   [0]         ...          [5]  value: "world" │
+    //    └────┼────────────────────────┼──────────────────┘
+    //         │                        │
+    //         │                        │
+    //         │                        │
+    //         │                        │
+    //   ┌───────┴──────────┐   ┌─────────┴─────────┐
+    //   │  Leaf Node       │   │  Leaf Node        │
+    //   │  Path: [4]       │   │  Path: [0]        │
+    //   │  Value: "hello1" │   │  Value: "hello2"  │
+    //   └──────────────────┘   └───────────────────┘
+    // func TestPutExtensionShorterAllMatched(t *testing.T) {
+    //   trie := NewTrie()
+    //   trie.Put([]byte{1, 2, 3, 4}, []byte("hello1"))
+    //   trie.Put([]byte{1, 2, 3, 5}, []byte("hello2"))
+    //   trie.Put([]byte{1, 2, 3}, []byte("world"))
+    //
+    //   leaf1 := NewLeafNodeFromNibbles([]Nibble{}, []byte("hello1"))
+    //   leaf2 := NewLeafNodeFromNibbles([]Nibble{}, []byte("hello2"))
+    //
+    //   branch1 := NewBranchNode()
+    //   branch1.SetBranch(Nibble(4), leaf1)
+    //   branch1.SetBranch(Nibble(5), leaf2)
+    //
+    //   branch2 := NewBranchNode()
+    //   branch2.SetValue([]byte("world"))
+    //   branch2.SetBranch(Nibble(0), branch1)
+    //
+    //   ext := NewExtensionNode([]Nibble{0, 1, 0, 2, 0, 3}, branch2)
+    //
+    //   require.Equal(t, ext.Hash(), trie.Hash())
+    // }
+    #[test]
+    fn test_put_extension_shorter_all_matched() {
+        let mut trie = Trie::new();
+        trie.put(&[1, 2, 3, 4], b"hello1");
+        trie.put(&[1, 2, 3, 5], b"hello2");
+        trie.put(&[1, 2, 3], b"world");
+
+        let leaf1 = LeafNode::new_leaf_node_from_nibbles(vec![], b"hello1".to_vec());
+        let leaf2 = LeafNode::new_leaf_node_from_nibbles(vec![], b"hello2".to_vec());
+
+        let mut branch1 = BranchNode::new();
+        branch1.set_branch(Nibble(4), Node::Leaf(leaf1));
+        branch1.set_branch(Nibble(5), Node::Leaf(leaf2));
+
+        let mut branch2 = BranchNode::new();
+        branch2.set_value(b"world".to_vec());
+        branch2.set_branch(Nibble(0), Node::Branch(Box::new(branch1)));
+
+        let ext = ExtensionNode::new(nibbles(&[0, 1, 0, 2, 0, 3]), Node::Branch(Box::new(branch2)));
+
+        assert_eq!(ext.hash(), trie.hash());
+    }
+
+    // func TestPutExtensionShorterPartialMatched(t *testing.T) {
+    //   trie := NewTrie()
+    //   trie.Put([]byte{1, 2, 3, 4}, []byte("hello1"))
+    //   trie.Put([]byte{1, 2, 3, 5}, []byte("hello2"))
+    //   trie.Put([]byte{1, 2, 5}, []byte("world"))
+    //
+    //   leaf1 := NewLeafNodeFromNibbles([]Nibble{}, []byte("hello1"))
+    //   leaf2 := NewLeafNodeFromNibbles([]Nibble{}, []byte("hello2"))
+    //
+    //   branch1 := NewBranchNode()
+    //   branch1.SetBranch(Nibble(4), leaf1)
+    //   branch1.SetBranch(Nibble(5), leaf2)
+    //
+    //   ext1 := NewExtensionNode([]Nibble{0}, branch1)
+    //
+    //   branch2 := NewBranchNode()
+    //   branch2.SetBranch(Nibble(3), ext1)
+    //   leaf3 := NewLeafNodeFromNibbles([]Nibble{}, []byte("world"))
+    //   branch2.SetBranch(Nibble(5), leaf3)
+    //
+    //   ext2 := NewExtensionNode([]Nibble{0, 1, 0, 2, 0}, branch2)
+    //
+    //   require.Equal(t, ext2.Hash(), trie.Hash())
+    // }
+    #[test]
+    fn test_put_extension_shorter_partial_matched() {
+        let mut trie = Trie::new();
+        trie.put(&[1, 2, 3, 4], b"hello1");
+        trie.put(&[1, 2, 3, 5], b"hello2");
+        trie.put(&[1, 2, 5], b"world");
+
+        let leaf1 = LeafNode::new_leaf_node_from_nibbles(vec![], b"hello1".to_vec());
+        let leaf2 = LeafNode::new_leaf_node_from_nibbles(vec![], b"hello2".to_vec());
+
+        let mut branch1 = BranchNode::new();
+        branch1.set_branch(Nibble(4), Node::Leaf(leaf1));
+        branch1.set_branch(Nibble(5), Node::Leaf(leaf2));
+
+        let ext1 = ExtensionNode::new(nibbles(&[0]), Node::Branch(Box::new(branch1)));
+
+        let mut branch2 = BranchNode::new();
+        branch2.set_branch(Nibble(3), Node::Extension(ext1));
+        let leaf3 = LeafNode::new_leaf_node_from_nibbles(vec![], b"world".to_vec());
+        branch2.set_branch(Nibble(5), Node::Leaf(leaf3));
+
+        let ext2 = ExtensionNode::new(nibbles(&[0, 1, 0, 2, 0]), Node::Branch(Box::new(branch2)));
+
+        assert_eq!(ext2.hash(), trie.hash());
+    }
+
+    // func TestPutExtensionShorterZeroMatched(t *testing.T) {
+    //   trie := NewTrie()
+    //   trie.Put([]byte{1, 2, 3, 4}, []byte("hello1"))
+    //   trie.Put([]byte{1, 2, 3, 5}, []byte("hello2"))
+    //   trie.Put([]byte{1 << 4, 2, 5}, []byte("world"))
+    //
+    //   leaf1 := NewLeafNodeFromNibbles([]Nibble{}, []byte("hello1"))
+    //   leaf2 := NewLeafNodeFromNibbles([]Nibble{}, []byte("hello2"))
+    //
+    //   branch1 := NewBranchNode()
+    //   branch1.SetBranch(Nibble(4), leaf1)
+    //   branch1.SetBranch(Nibble(5), leaf2)
+    //
+    //   ext1 := NewExtensionNode([]Nibble{1, 0, 2, 0, 3, 0}, branch1)
+    //
+    //   branch2 := NewBranchNode()
+    //   branch2.SetBranch(Nibble(0), ext1)
+    //   leaf3 := NewLeafNodeFromNibbles([]Nibble{0, 0, 2, 0, 5}, []byte("world"))
+    //   branch2.SetBranch(Nibble(1), leaf3)
+    //
+    //   require.Equal(t, branch2.Hash(), trie.Hash())
+    // }
+    #[test]
+    fn test_put_extension_shorter_zero_matched() {
+        let mut trie = Trie::new();
+        trie.put(&[1, 2, 3, 4], b"hello1");
+        trie.put(&[1, 2, 3, 5], b"hello2");
+        trie.put(&[1 << 4, 2, 5], b"world");
+
+        let leaf1 = LeafNode::new_leaf_node_from_nibbles(vec![], b"hello1".to_vec());
+        let leaf2 = LeafNode::new_leaf_node_from_nibbles(vec![], b"hello2".to_vec());
+
+        let mut branch1 = BranchNode::new();
+        branch1.set_branch(Nibble(4), Node::Leaf(leaf1));
+        branch1.set_branch(Nibble(5), Node::Leaf(leaf2));
+
+        let ext1 = ExtensionNode::new(nibbles(&[1, 0, 2, 0, 3, 0]), Node::Branch(Box::new(branch1)));
+
+        let mut branch2 = BranchNode::new();
+        branch2.set_branch(Nibble(0), Node::Extension(ext1));
+        let leaf3 = LeafNode::new_leaf_node_from_nibbles(nibbles(&[0, 0, 2, 0, 5]), b"world".to_vec());
+        branch2.set_branch(Nibble(1), Node::Leaf(leaf3));
+
+        assert_eq!(branch2.hash(), trie.hash());
+    }
+
+    // func TestPutExtensionAllMatched(t *testing.T) {
+    //   trie := NewTrie()
+    //   trie.Put([]byte{1, 2, 3, 4}, []byte("hello1"))
+    //   trie.Put([]byte{1, 2, 3, 5 << 4}, []byte("hello2"))
+    //   trie.Put([]byte{1, 2, 3}, []byte("world"))
+    //
+    //   leaf1 := NewLeafNodeFromNibbles([]Nibble{4}, []byte("hello1"))
+    //   leaf2 := NewLeafNodeFromNibbles([]Nibble{0}, []byte("hello2"))
+    //
+    //   branch := NewBranchNode()
+    //   branch.SetBranch(Nibble(0), leaf1)
+    //   branch.SetBranch(Nibble(5), leaf2)
+    //   branch.SetValue([]byte("world"))
+    //
+    //   ext := NewExtensionNode([]Nibble{0, 1, 0, 2, 0, 3}, branch)
+    //
+    //   require.Equal(t, ext.Hash(), trie.Hash())
+    // }
+    #[test]
+    fn test_put_extension_all_matched() {
+        let mut trie = Trie::new();
+        trie.put(&[1, 2, 3, 4], b"hello1");
+        trie.put(&[1, 2, 3, 5 << 4], b"hello2");
+        trie.put(&[1, 2, 3], b"world");
+
+        let leaf1 = LeafNode::new_leaf_node_from_nibbles(nibbles(&[4]), b"hello1".to_vec());
+        let leaf2 = LeafNode::new_leaf_node_from_nibbles(nibbles(&[0]), b"hello2".to_vec());
+
+        let mut branch = BranchNode::new();
+        branch.set_branch(Nibble(0), Node::Leaf(leaf1));
+        branch.set_branch(Nibble(5), Node::Leaf(leaf2));
+        branch.set_value(b"world".to_vec());
+
+        let ext = ExtensionNode::new(nibbles(&[0, 1, 0, 2, 0, 3]), Node::Branch(Box::new(branch)));
+
+        assert_eq!(ext.hash(), trie.hash());
+    }
+
+    // func TestPutExtensionMore(t *testing.T) {
+    //   trie := NewTrie()
+    //   trie.Put([]byte{1, 2, 3, 4}, []byte("hello1"))
+    //   trie.Put([]byte{1, 2, 3, 5}, []byte("hello2"))
+    //   trie.Put([]byte{1, 2, 3, 6}, []byte("world"))
+    //
+    //   leaf1 := NewLeafNodeFromNibbles([]Nibble{}, []byte("hello1"))
+    //   leaf2 := NewLeafNodeFromNibbles([]Nibble{}, []byte("hello2"))
+    //   leaf3 := NewLeafNodeFromNibbles([]Nibble{}, []byte("world"))
+    //
+    //   branch := NewBranchNode()
+    //   branch.SetBranch(Nibble(4), leaf1)
+    //   branch.SetBranch(Nibble(5), leaf2)
+    //   branch.SetBranch(Nibble(6), leaf3)
+    //
+    //   ext := NewExtensionNode([]Nibble{0, 1, 0, 2, 0, 3, 0}, branch)
+    //
+    //   require.Equal(t, ext.Hash(), trie.Hash())
+    // }
+    #[test]
+    fn test_put_extension_more() {
+        let mut trie = Trie::new();
+        trie.put(&[1, 2, 3, 4], b"hello1");
+        trie.put(&[1, 2, 3, 5], b"hello2");
+        trie.put(&[1, 2, 3, 6], b"world");
+
+        let leaf1 = LeafNode::new_leaf_node_from_nibbles(vec![], b"hello1".to_vec());
+        let leaf2 = LeafNode::new_leaf_node_from_nibbles(vec![], b"hello2".to_vec());
+        let leaf3 = LeafNode::new_leaf_node_from_nibbles(vec![], b"world".to_vec());
+
+        let mut branch = BranchNode::new();
+        branch.set_branch(Nibble(4), Node::Leaf(leaf1));
+        branch.set_branch(Nibble(5), Node::Leaf(leaf2));
+        branch.set_branch(Nibble(6), Node::Leaf(leaf3));
+
+        let ext = ExtensionNode::new(nibbles(&[0, 1, 0, 2, 0, 3, 0]), Node::Branch(Box::new(branch)));
+
+        assert_eq!(ext.hash(), trie.hash());
+    }
+
+    #[test]
+    fn test_delete() {
+        // should not delete a key that does not exist
+        let mut trie = Trie::new();
+        assert!(!trie.delete(b"notexist"));
+        trie.put(&[1, 2, 3, 4], b"hello");
+        assert!(!trie.delete(&[1, 2, 3]));
+        assert!(!trie.delete(&[1, 2, 3, 4, 5]));
+
+        // should delete the only key
+        assert!(trie.delete(&[1, 2, 3, 4]));
+        assert_eq!(trie.get(&[1, 2, 3, 4]), None);
+        assert_eq!(EMPTY_NODE_HASH.to_vec(), trie.hash());
+    }
+
+    #[test]
+    fn test_delete_branch_value() {
+        let mut trie = Trie::new();
+        trie.put(&[1, 2, 3, 4], b"hello");
+        trie.put(&[1, 2, 3, 4, 5, 6], b"world");
+
+        assert!(trie.delete(&[1, 2, 3, 4]));
+        assert_eq!(trie.get(&[1, 2, 3, 4]), None);
+        assert_eq!(trie.get(&[1, 2, 3, 4, 5, 6]), Some(b"world".to_vec()));
+
+        // E 01020304 -> B [0: L 506]
+        // => L 01020304 0506
+        let leaf = LeafNode::new_from_bytes(&[1, 2, 3, 4, 5, 6], b"world");
+        assert_eq!(leaf.hash(), trie.hash());
+    }
+
+    #[test]
+    fn test_delete_merges_extensions() {
+        let mut trie = Trie::new();
+        trie.put(&[1, 2, 3, 4], b"hello1");
+        trie.put(&[1, 2, 3, 5], b"hello2");
+        trie.put(&[1, 2, 5], b"world");
+
+        // E 01020 -> B [3: E 0 -> B [4, 5], 5: L]
+        // => E 0102030 -> B [4, 5]
+        assert!(trie.delete(&[1, 2, 5]));
+
+        let mut expected = Trie::new();
+        expected.put(&[1, 2, 3, 4], b"hello1");
+        expected.put(&[1, 2, 3, 5], b"hello2");
+        assert_eq!(expected.hash(), trie.hash());
+
+        let Node::Extension(ext) = &trie.root else { panic!("root is not an extension node") };
+        assert_eq!(nibbles(&[0, 1, 0, 2, 0, 3, 0]), ext.path);
+    }
+
+    // keys are drawn from a small alphabet so that they share prefixes and
+    // exercise branch values, extensions and collapsing
+    fn small_keys(pairs: Vec<(Vec<u8>, u8)>) -> Vec<(Vec<u8>, Vec<u8>)> {
+        pairs
+            .into_iter()
+            .map(|(key, value)| {
+                let key: Vec<u8> = key.iter().take(4).map(|b| b % 3).collect();
+                (key, vec![value, 1])
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_delete_matches_fresh_trie() {
+        fn prop(pairs: Vec<(Vec<u8>, u8)>, deletes: Vec<usize>) -> bool {
+            let pairs = small_keys(pairs);
+            if pairs.is_empty() {
+                return true;
+            }
+
+            let mut trie = Trie::new();
+            for (key, value) in &pairs {
+                trie.put(key, value);
+            }
+
+            let deleted: Vec<&Vec<u8>> = deletes.iter().map(|i| &pairs[i % pairs.len()].0).collect();
+            for key in &deleted {
+                trie.delete(key);
+            }
+
+            let mut expected = Trie::new();
+            for (key, value) in pairs.iter().filter(|(key, _)| !deleted.contains(&key)) {
+                expected.put(key, value);
+            }
+
+            trie.hash() == expected.hash() && deleted.iter().all(|key| trie.get(key).is_none())
+        }
+        quickcheck::quickcheck(prop as fn(Vec<(Vec<u8>, u8)>, Vec<usize>) -> bool);
+    }
+
+    #[test]
+    fn test_put_then_delete_all_is_empty() {
+        fn prop(pairs: Vec<(Vec<u8>, u8)>) -> bool {
+            let pairs = small_keys(pairs);
+            let mut trie = Trie::new();
+            for (key, value) in &pairs {
+                trie.put(key, value);
+            }
+            for (key, _) in &pairs {
+                trie.delete(key);
+            }
+            trie.hash() == EMPTY_NODE_HASH.to_vec() && trie.root.is_empty()
+        }
+        quickcheck::quickcheck(prop as fn(Vec<(Vec<u8>, u8)>) -> bool);
+    }
+
+    #[test]
+    fn test_entries_in_key_order() {
+        fn prop(pairs: Vec<(Vec<u8>, u8)>) -> bool {
+            let model: std::collections::BTreeMap<Vec<u8>, Vec<u8>> =
+                pairs.into_iter().map(|(key, value)| (key, vec![value; 1 + value as usize % 40])).collect();
+            let mut trie = Trie::new();
+            for (key, value) in &model {
+                trie.put(key, value);
+            }
+            trie.entries() == model.into_iter().collect::<Vec<_>>()
+        }
+        quickcheck::quickcheck(prop as fn(Vec<(Vec<u8>, u8)>) -> bool);
+
+        // a key that is a prefix of another ends at a branch node
+        let mut trie = Trie::new();
+        trie.put(&[1, 2], b"b");
+        trie.put(&[1], b"a");
+        trie.put(&[2], b"c");
+        assert_eq!(vec![(vec![1], b"a".to_vec()), (vec![1, 2], b"b".to_vec()), (vec![2], b"c".to_vec())], trie.entries());
+        assert!(Trie::new().entries().is_empty());
+    }
+
+    // the cached hashes of the nodes on the path of each update are cleared,
+    // so hashing after every update gives the root of a fresh trie
+    #[test]
+    fn test_cached_hash_matches_fresh_trie() {
+        fn prop(updates: Vec<(Vec<u8>, u8, bool)>) -> bool {
+            let mut trie = Trie::new();
+            let mut model = std::collections::BTreeMap::new();
+            for (key, value, delete) in updates.into_iter().take(30) {
+                let key: Vec<u8> = key.iter().take(4).map(|b| b % 3).collect();
+                if delete {
+                    trie.delete(&key);
+                    model.remove(&key);
+                } else {
+                    // long enough values for some nodes to be hashed rather than embedded
+                    let value = vec![value; 1 + value as usize % 40];
+                    trie.put(&key, &value);
+                    model.insert(key, value);
+                }
+
+                let mut fresh = Trie::new();
+                for (key, value) in &model {
+                    fresh.put(key, value);
+                }
+                if trie.hash() != fresh.hash() {
+                    return false;
+                }
+            }
+            true
+        }
+        quickcheck::quickcheck(prop as fn(Vec<(Vec<u8>, u8, bool)>) -> bool);
+    }
+}
@@ -0,0 +1,129 @@
+use rlp::{Decodable, DecoderError, Encodable, Rlp, RlpStream};
+use serde::Deserialize;
+
+use crate::hashing::DerivableList;
+use crate::hexutil;
+use crate::transaction::decode_fixed;
+
+// Withdrawal represents a validator withdrawal from the consensus layer.
+// type Withdrawal struct {
+// 	Index     uint64         `json:"index"`          // monotonically increasing identifier issued by consensus layer
+// 	Validator uint64         `json:"validatorIndex"` // index of validator associated with withdrawal
+// 	Address   common.Address `json:"address"`        // target address for withdrawn ether
+// 	Amount    uint64         `json:"amount"`         // value of withdrawal in Gwei
+// }
+//
+// The withdrawals of a block are committed to by the withdrawalsRoot of its
+// header since Shanghai, with the same index-keyed trie as the transactions.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct Withdrawal {
+    #[serde(deserialize_with = "hexutil::uint64")]
+    pub index: u64,
+    #[serde(rename = "validatorIndex", deserialize_with = "hexutil::uint64")]
+    pub validator: u64,
+    #[serde(deserialize_with = "hexutil::fixed")]
+    pub address: [u8; 20],
+    #[serde(deserialize_with = "hexutil::uint64")]
+    pub amount: u64,
+}
+
+impl Encodable for Withdrawal {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        s.begin_list(4);
+        s.append(&self.index);
+        s.append(&self.validator);
+        s.append(&&self.address[..]);
+        s.append(&self.amount);
+    }
+}
+
+impl Decodable for Withdrawal {
+    fn decode(rlp: &Rlp) -> Result<Withdrawal, DecoderError> {
+        if rlp.item_count()? != 4 {
+            return Err(DecoderError::RlpIncorrectListLen);
+        }
+        Ok(Withdrawal {
+            index: rlp.val_at(0)?,
+            validator: rlp.val_at(1)?,
+            address: decode_fixed(&rlp.at(2)?)?,
+            amount: rlp.val_at(3)?,
+        })
+    }
+}
+
+// func (s Withdrawals) EncodeIndex(i int, w *bytes.Buffer) {
+// 	rlp.Encode(w, s[i])
+// }
+impl DerivableList for [Withdrawal] {
+    fn len(&self) -> usize {
+        <[Withdrawal]>::len(self)
+    }
+
+    fn encode_index(&self, i: usize, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&rlp::encode(&self[i]));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::empty::EMPTY_NODE_HASH;
+    use crate::hashing::derive_root;
+    use crate::header::Header;
+    use crate::trie::Trie;
+
+    // made-up withdrawals in the format of the eth_getBlockByNumber response
+    const WITHDRAWALS: &str = r#"[
+        {"index": "0x1b5c6d8", "validatorIndex": "0x8f3e1", "address": "0xb9d7934878b5fb9610b3fe8a5e441e8fad7e293f", "amount": "0x11f9e3c"},
+        {"index": "0x1b5c6d9", "validatorIndex": "0x8f3e2", "address": "0xb9d7934878b5fb9610b3fe8a5e441e8fad7e293f", "amount": "0x11e1a6f"},
+        {"index": "0x1b5c6da", "validatorIndex": "0x8f3e3", "address": "0x210b3cb99fa1de0a64085fa80e18c22fe4722a1b", "amount": "0x773594000"},
+        {"index": "0x1b5c6db", "validatorIndex": "0x8f3e4", "address": "0x0000000000000000000000000000000000000000", "amount": "0x0"}
+    ]"#;
+
+    #[test]
+    fn test_withdrawal_encoding() {
+        let withdrawals: Vec<Withdrawal> = serde_json::from_str(WITHDRAWALS).unwrap();
+        assert_eq!(0x1b5c6d8, withdrawals[0].index);
+        assert_eq!(0x8f3e1, withdrawals[0].validator);
+        assert_eq!(0x11f9e3c, withdrawals[0].amount);
+        // a full exit of 32 ETH, in gwei
+        assert_eq!(32_000_000_000, withdrawals[2].amount);
+
+        let encoded = rlp::encode_list(&withdrawals);
+        assert_eq!(withdrawals, rlp::decode_list::<Withdrawal>(&encoded));
+
+        // a zero amount is the empty string
+        let b = rlp::encode(&withdrawals[3]);
+        assert_eq!(0x80, b[b.len() - 1]);
+
+        let mut s = RlpStream::new_list(3);
+        s.append(&1u64).append(&2u64).append(&&[0u8; 20][..]);
+        assert_eq!(Err(DecoderError::RlpIncorrectListLen), rlp::decode::<Withdrawal>(&s.out()));
+    }
+
+    #[test]
+    fn test_withdrawals_root() {
+        let withdrawals: Vec<Withdrawal> = serde_json::from_str(WITHDRAWALS).unwrap();
+        let mut tr = Trie::new();
+        for (i, w) in withdrawals.iter().enumerate() {
+            tr.put(&rlp::encode(&(i as u64)), &rlp::encode(w));
+        }
+        // the withdrawals are made up, so there is no withdrawalsRoot to check
+        // the root against, only the trie they are put in
+        let root = derive_root(withdrawals.as_slice());
+        assert_eq!(tr.hash(), root);
+
+        // a Shanghai block without withdrawals commits to the empty trie
+        let empty: &[Withdrawal] = &[];
+        assert_eq!(EMPTY_NODE_HASH.to_vec(), derive_root(empty));
+
+        // the header verifier checks the root of the block body
+        let header = Header {
+            base_fee: Some(vec![0x07]),
+            withdrawals_hash: Some(root.as_slice().try_into().unwrap()),
+            ..Header::default()
+        };
+        let decoded: Header = rlp::decode(&rlp::encode(&header)).unwrap();
+        assert_eq!(Some(root.as_slice()), decoded.withdrawals_hash.as_ref().map(|h| &h[..]));
+    }
+}